@@ -0,0 +1,223 @@
+//! Everything that can be done with a key, so the keymap and the command
+//! palette share one list.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Top,
+    Bottom,
+    PageDown,
+    PageUp,
+    HalfPageDown,
+    HalfPageUp,
+
+    Edit,
+    AddItem,
+    ClearField,
+    DeleteItem,
+    ArchiveItem,
+    OpenItem,
+    Undo,
+    Redo,
+
+    ToggleSelect,
+    VisualSelect,
+    ClearSelection,
+
+    Filter,
+    ClearFilter,
+    SortBy,
+    ClearSort,
+    HideField,
+    ShowAllFields,
+    GroupBy,
+    ToggleGroup,
+    CollapseGroups,
+    ExpandGroups,
+    NextView,
+    PreviousView,
+    SaveView,
+    DefaultView,
+
+    WidenColumn,
+    NarrowColumn,
+    ResetColumnWidth,
+    FitToTerminal,
+    ToggleFooter,
+
+    SwitchProject,
+    SwitchAccount,
+    Export,
+    CommandPalette,
+    Insights,
+    Help,
+    Quit,
+
+    // Lists, the calendar and prompts
+    Confirm,
+    Cancel,
+    NextMonth,
+    PreviousMonth,
+    NextYear,
+    PreviousYear,
+    Today,
+}
+
+impl Action {
+    /// Actions that can be run from the table
+    pub const ALL: &'static [Action] = &[
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Top,
+        Action::Bottom,
+        Action::PageDown,
+        Action::PageUp,
+        Action::HalfPageDown,
+        Action::HalfPageUp,
+        Action::Edit,
+        Action::AddItem,
+        Action::ClearField,
+        Action::DeleteItem,
+        Action::ArchiveItem,
+        Action::OpenItem,
+        Action::Undo,
+        Action::Redo,
+        Action::ToggleSelect,
+        Action::VisualSelect,
+        Action::ClearSelection,
+        Action::Filter,
+        Action::ClearFilter,
+        Action::SortBy,
+        Action::ClearSort,
+        Action::HideField,
+        Action::ShowAllFields,
+        Action::GroupBy,
+        Action::ToggleGroup,
+        Action::CollapseGroups,
+        Action::ExpandGroups,
+        Action::NextView,
+        Action::PreviousView,
+        Action::SaveView,
+        Action::DefaultView,
+        Action::WidenColumn,
+        Action::NarrowColumn,
+        Action::ResetColumnWidth,
+        Action::FitToTerminal,
+        Action::ToggleFooter,
+        Action::SwitchProject,
+        Action::SwitchAccount,
+        Action::Export,
+        Action::CommandPalette,
+        Action::Insights,
+        Action::Help,
+        Action::Quit,
+    ];
+
+    /// Name shown in the command palette
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Up => "Move up",
+            Action::Down => "Move down",
+            Action::Left => "Move left",
+            Action::Right => "Move right",
+            Action::Top => "Go to first item",
+            Action::Bottom => "Go to last item",
+            Action::PageDown => "Page down",
+            Action::PageUp => "Page up",
+            Action::HalfPageDown => "Half page down",
+            Action::HalfPageUp => "Half page up",
+            Action::Edit => "Edit field",
+            Action::AddItem => "Add item",
+            Action::ClearField => "Clear field",
+            Action::DeleteItem => "Delete item",
+            Action::ArchiveItem => "Archive item",
+            Action::OpenItem => "Open item in browser",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::ToggleSelect => "Select item",
+            Action::VisualSelect => "Select range",
+            Action::ClearSelection => "Clear selection",
+            Action::Filter => "Filter items",
+            Action::ClearFilter => "Clear filter",
+            Action::SortBy => "Sort by field",
+            Action::ClearSort => "Clear sort",
+            Action::HideField => "Hide field",
+            Action::ShowAllFields => "Show all fields",
+            Action::GroupBy => "Group by field",
+            Action::ToggleGroup => "Fold or unfold group",
+            Action::CollapseGroups => "Fold all groups",
+            Action::ExpandGroups => "Unfold all groups",
+            Action::NextView => "Next view",
+            Action::PreviousView => "Previous view",
+            Action::SaveView => "Save view",
+            Action::DefaultView => "Open project with this view",
+            Action::WidenColumn => "Widen column",
+            Action::NarrowColumn => "Narrow column",
+            Action::ResetColumnWidth => "Reset column width",
+            Action::FitToTerminal => "Fit columns to terminal",
+            Action::ToggleFooter => "Show or hide column totals",
+            Action::SwitchProject => "Switch project",
+            Action::SwitchAccount => "Switch account",
+            Action::Export => "Export items",
+            Action::CommandPalette => "Command palette",
+            Action::Insights => "Project insights",
+            Action::Help => "Show keys",
+            Action::Quit => "Quit",
+            Action::Confirm => "Confirm",
+            Action::Cancel => "Cancel",
+            Action::NextMonth => "Next month",
+            Action::PreviousMonth => "Previous month",
+            Action::NextYear => "Next year",
+            Action::PreviousYear => "Previous year",
+            Action::Today => "Today",
+        }
+    }
+}
+
+/// Actions whose description contains the letters of `query` in order, best
+/// matches first
+pub fn search(query: &str) -> Vec<Action> {
+    let mut scored: Vec<(i32, Action)> = Action::ALL
+        .iter()
+        .filter(|a| **a != Action::CommandPalette)
+        .filter_map(|a| fuzzy_score(query, a.description()).map(|score| (score, *a)))
+        .collect();
+
+    // Stable, so equal scores keep the order of `Action::ALL`
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, a)| a).collect()
+}
+
+/// Scores a case insensitive subsequence match, favouring letters that start
+/// words or follow each other. `None` when not every letter is found.
+fn fuzzy_score(query: &str, target: &str) -> Option<i32> {
+    let target: Vec<char> = target.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut last_match: Option<usize> = None;
+
+    for c in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = position + target[position..].iter().position(|t| *t == c)?;
+
+        score += 1;
+        if found == 0 || target[found - 1] == ' ' {
+            score += 3;
+        }
+        if last_match.is_some_and(|last| last + 1 == found) {
+            score += 2;
+        }
+
+        last_match = Some(found);
+        position = found + 1;
+    }
+
+    Some(score)
+}
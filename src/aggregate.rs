@@ -0,0 +1,79 @@
+//! Totals of a field over the items shown, drawn in the footer under the
+//! table
+
+use crate::app::Config;
+use crate::group;
+use crate::project::*;
+
+/// Summary of one field's values, empty when there is nothing to show
+pub fn summary(config: &Config, field: &Field, items: &[&Item]) -> String {
+    let name = field.get_name();
+
+    match field.get_type() {
+        "TITLE" => format!("{} items", items.len()),
+        "NUMBER" => {
+            let numbers: Vec<f32> = items
+                .iter()
+                .filter_map(|item| match item.field_values.get_from_field(name) {
+                    ProjectV2ItemField::NumberValue { number, .. } => Some(*number),
+                    _ => None,
+                })
+                .collect();
+            if numbers.is_empty() {
+                return String::new();
+            }
+
+            let sum: f32 = numbers.iter().sum();
+            let min = numbers.iter().copied().fold(f32::INFINITY, f32::min);
+            let max = numbers.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            let format = |number| config.display_number(name, number);
+
+            format!(
+                "Σ {} · avg {} · {}–{}",
+                format(sum),
+                format(sum / numbers.len() as f32),
+                format(min),
+                format(max)
+            )
+        }
+        "DATE" => {
+            // Dates are YYYY-MM-DD, so they sort as text
+            let dates: Vec<String> = items
+                .iter()
+                .map(|item| item.field_values.name_from_field(name))
+                .filter(|date| !date.is_empty())
+                .collect();
+
+            match (dates.iter().min(), dates.iter().max()) {
+                (Some(first), Some(last)) if first == last => first.clone(),
+                (Some(first), Some(last)) => format!("{first} – {last}"),
+                _ => String::new(),
+            }
+        }
+        "SINGLE_SELECT" => {
+            let Field::ProjectV2SingleSelectField(select) = field else {
+                return String::new();
+            };
+
+            // In the order the options are set up in
+            select
+                .options
+                .iter()
+                .map(|option| {
+                    let count = items
+                        .iter()
+                        .filter(|item| item.field_values.name_from_field(name) == option.name)
+                        .count();
+                    (option, count)
+                })
+                .filter(|(_, count)| *count > 0)
+                .map(|(option, count)| format!("{} {count}", option.name))
+                .collect::<Vec<String>>()
+                .join(" · ")
+        }
+        _ => {
+            let set = items.iter().filter(|item| !group::value(item, field).is_empty()).count();
+            format!("{set}/{} set", items.len())
+        }
+    }
+}
@@ -1,28 +1,112 @@
-use crate::github;
+use crate::action::{self, Action};
+use crate::bulk::{BulkAction, BulkJob};
+use crate::credentials::{self, Login, Saved, TokenSource, DEFAULT_ACCOUNT};
+use crate::dates;
+use crate::editor::{EditResult, LineEditor, VimMode};
+use crate::export::{self, ExportFormat};
+use crate::filter::Filter;
+use crate::group::{self, Group, TableRow};
+use crate::github::{self, Auth};
+use crate::history::{Change, FieldValue, History, PartlyApplied};
+use crate::keymap::{Bindings, KeyPress, Keymap, Lookup, Mode};
+use crate::number;
 use crate::project::*;
+use crate::sort::{self, Sort};
+use crate::theme::{Theme, ThemeConfig};
+use crate::view::{ProjectViews, View};
+use crate::ui::MIN_COLUMN_WIDTH;
 use ::time::Date;
 use anyhow::anyhow;
-use crossterm::event::{KeyCode, KeyEvent};
-use github_device_flow::Credential;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::string::String;
-use time::format_description;
 use time::Duration;
+use time::UtcOffset;
 
 #[derive(PartialEq, Debug, Clone)]
 pub enum InputMode {
     Normal,
     Input,
     SwitchProject(usize),
-    AddItem(String, usize),
+    SwitchAccount(usize),
+    AddItem(LineEditor),
+    Export(LineEditor),
+    Filter(LineEditor),
+    /// Name to save the current view under
+    SaveView(LineEditor),
+    /// Search for an action, and which match is highlighted
+    Palette(LineEditor, usize),
+    /// Every key binding, scrolled down by some lines
+    Help(u16),
+    /// Charts of the project, with a burn-up rather than a burn-down when set
+    Insights(bool),
+    /// Asks before deleting, or archiving several items, run on `y`
+    Confirm(Action),
     LoadingProject,
     Error(String),
+    Message(String),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Config {
     pub project_state: usize,
     pub field_ignore: Vec<FieldIgnore>,
+    /// Number of leading columns kept in place when scrolling sideways
+    #[serde(default = "default_frozen_columns")]
+    pub frozen_columns: usize,
+    /// Widths set by hand for single columns
+    #[serde(default)]
+    pub column_widths: Vec<ColumnWidth>,
+    /// Longest a column grows to fit its content
+    #[serde(default = "default_max_column_width")]
+    pub max_column_width: u16,
+    /// Shrink the widest columns so every column fits on screen
+    #[serde(default)]
+    pub fit_to_terminal: bool,
+    /// Totals of each column under the table
+    #[serde(default = "default_footer")]
+    pub footer: bool,
+    /// Vim style normal mode in text editors, entered with Esc
+    #[serde(default)]
+    pub vim_editor: bool,
+    /// Keys that differ from the defaults
+    #[serde(default)]
+    pub keymap: Keymap,
+    /// Ask for a passphrase to encrypt the saved login
+    #[serde(default)]
+    pub encrypt_credentials: bool,
+    /// GitHub accounts to switch between, a default one is used when empty
+    #[serde(default)]
+    pub accounts: Vec<Account>,
+    /// Name of the account in use
+    #[serde(default)]
+    pub account: String,
+    /// List the projects of every logged in account together
+    #[serde(default)]
+    pub merge_accounts: bool,
+    /// How number fields are shown, by field name
+    #[serde(default)]
+    pub number_formats: Vec<NumberFormat>,
+    /// Colors and text styles
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    /// Views saved locally, by project
+    #[serde(default)]
+    pub views: Vec<ProjectViews>,
+}
+
+fn default_frozen_columns() -> usize {
+    1
+}
+
+fn default_max_column_width() -> u16 {
+    40
+}
+
+fn default_footer() -> bool {
+    true
 }
 
 impl ::std::default::Default for Config {
@@ -30,10 +114,230 @@ impl ::std::default::Default for Config {
         Config {
             project_state: 0,
             field_ignore: Vec::new(),
+            frozen_columns: default_frozen_columns(),
+            column_widths: Vec::new(),
+            max_column_width: default_max_column_width(),
+            fit_to_terminal: false,
+            footer: default_footer(),
+            vim_editor: false,
+            keymap: Keymap::default(),
+            encrypt_credentials: false,
+            accounts: Vec::new(),
+            account: String::new(),
+            merge_accounts: false,
+            number_formats: Vec::new(),
+            theme: ThemeConfig::default(),
+            views: Vec::new(),
         }
     }
 }
 
+impl Config {
+    /// Accounts from the config, or the default one if there are none
+    pub fn accounts(&self) -> Vec<Account> {
+        match self.accounts.is_empty() {
+            true => vec![Account::default()],
+            false => self.accounts.clone(),
+        }
+    }
+
+    /// Account in use, the first one if the name isn't found
+    pub fn account(&self) -> Account {
+        let accounts = self.accounts();
+        accounts
+            .iter()
+            .find(|a| a.name == self.account)
+            .unwrap_or(&accounts[0])
+            .clone()
+    }
+
+    /// Remembers the project last opened with its account
+    pub fn remember_project(&mut self, project: &Project) {
+        if self.accounts.is_empty() {
+            self.accounts.push(Account::default());
+        }
+
+        if let Some(account) = self.accounts.iter_mut().find(|a| a.name == project.account) {
+            account.project = Some(SavedProject {
+                id: project.id.clone(),
+                owner: project.owner.login.clone(),
+                title: project.title.clone(),
+            });
+        }
+    }
+
+    /// Fields of a project that haven't been hidden
+    pub fn visible_fields<'a>(&self, project_id: &str, fields: &'a [Field]) -> Vec<&'a Field> {
+        let ignored = self.field_ignore.iter().find(|f| f.project_id == project_id);
+
+        fields
+            .iter()
+            .filter(|f| ignored.is_none_or(|i| !i.ignore.iter().any(|n| n == f.get_name())))
+            .collect()
+    }
+
+    pub fn is_field_hidden(&self, project_id: &str, field: &str) -> bool {
+        self.field_ignore
+            .iter()
+            .any(|f| f.project_id == project_id && f.ignore.iter().any(|n| n == field))
+    }
+
+    pub fn hide_field(&mut self, project_id: &str, field: &str) {
+        match self.field_ignore.iter_mut().find(|f| f.project_id == project_id) {
+            Some(ignored) => ignored.ignore.push(field.to_string()),
+            None => self.field_ignore.push(FieldIgnore {
+                project_id: project_id.to_string(),
+                ignore: vec![field.to_string()],
+            }),
+        }
+    }
+
+    pub fn show_all_fields(&mut self, project_id: &str) {
+        self.field_ignore.retain(|f| f.project_id != project_id);
+    }
+
+    pub fn project_views(&self, project_id: &str) -> Option<&ProjectViews> {
+        self.views.iter().find(|v| v.project_id == project_id)
+    }
+
+    fn project_views_mut(&mut self, project_id: &str) -> &mut ProjectViews {
+        if let Some(index) = self.views.iter().position(|v| v.project_id == project_id) {
+            return &mut self.views[index];
+        }
+
+        self.views.push(ProjectViews {
+            project_id: project_id.to_string(),
+            ..Default::default()
+        });
+        self.views.last_mut().unwrap()
+    }
+
+    /// Name of the view a project opens with
+    pub fn default_view(&self, project_id: &str) -> Option<&str> {
+        self.project_views(project_id).and_then(|v| v.default.as_deref())
+    }
+
+    /// Saves a local view, replacing one with the same name
+    pub fn save_view(&mut self, project_id: &str, view: View) {
+        let saved = self.project_views_mut(project_id);
+
+        match saved.views.iter_mut().find(|v| v.name == view.name) {
+            Some(existing) => *existing = view,
+            None => saved.views.push(view),
+        }
+    }
+
+    pub fn set_default_view(&mut self, project_id: &str, name: Option<String>) {
+        self.project_views_mut(project_id).default = name;
+    }
+
+    pub fn column_width(&self, project_id: &str, field: &str) -> Option<u16> {
+        self.column_widths
+            .iter()
+            .find(|c| c.project_id == project_id && c.field == field)
+            .map(|c| c.width)
+    }
+
+    /// Sets the width of a column, `None` goes back to fitting its content
+    pub fn set_column_width(&mut self, project_id: &str, field: &str, width: Option<u16>) {
+        self.column_widths
+            .retain(|c| !(c.project_id == project_id && c.field == field));
+
+        if let Some(width) = width {
+            self.column_widths.push(ColumnWidth {
+                project_id: project_id.to_string(),
+                field: field.to_string(),
+                width,
+            });
+        }
+    }
+
+    /// Value of an item field as shown in the table
+    pub fn display_value(&self, value: &ProjectV2ItemField) -> String {
+        match value {
+            ProjectV2ItemField::NumberValue { number, field } => self.display_number(field.get_name(), *number),
+            _ => value.value(),
+        }
+    }
+
+    /// A number in the format set for its field
+    pub fn display_number(&self, field: &str, number: f32) -> String {
+        match self.number_formats.iter().find(|f| f.field == field) {
+            Some(format) => format.apply(number),
+            None => number.to_string(),
+        }
+    }
+}
+
+/// A GitHub login to switch to, with its own saved credential
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Account {
+    pub name: String,
+    /// `github.com` or a GitHub Enterprise host
+    #[serde(default = "default_host")]
+    pub host: String,
+    /// Project last opened with this account
+    #[serde(default)]
+    pub project: Option<SavedProject>,
+}
+
+/// A project kept by its node ID, which unlike its place in the list doesn't
+/// change when other projects are added or deleted
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedProject {
+    pub id: String,
+    #[serde(default)]
+    pub owner: String,
+    /// Only used to say which project is gone
+    #[serde(default)]
+    pub title: String,
+}
+
+fn default_host() -> String {
+    String::from("github.com")
+}
+
+impl Default for Account {
+    fn default() -> Account {
+        Account {
+            name: String::from(DEFAULT_ACCOUNT),
+            host: default_host(),
+            project: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ColumnWidth {
+    pub project_id: String,
+    pub field: String,
+    pub width: u16,
+}
+
+/// Display format for a number field, e.g. `precision = 2` and `prefix = "$"`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct NumberFormat {
+    pub field: String,
+    /// Decimal places to round to
+    #[serde(default)]
+    pub precision: Option<usize>,
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default)]
+    pub suffix: String,
+}
+
+impl NumberFormat {
+    pub fn apply(&self, number: f32) -> String {
+        let number = match self.precision {
+            Some(precision) => format!("{number:.precision$}"),
+            None => number.to_string(),
+        };
+
+        format!("{}{number}{}", self.prefix, self.suffix)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct FieldIgnore {
     pub project_id: String,
@@ -52,24 +356,106 @@ pub(crate) struct App {
 
     pub user_info: Option<UserInfo>,
 
-    pub id: Option<Credential>,
+    /// Tokens of the accounts logged in to so far, by account name
+    pub logins: HashMap<String, Login>,
+    /// Set when another account is picked, so the editor closes to log in
+    pub relogin: bool,
+    /// Project given with `--project`, opened once logged in
+    pub open_project: Option<String>,
+    /// Local timezone, read before any threads start
+    pub utc_offset: UtcOffset,
     pub input: FieldBuffer,
+
+    pub history: History,
+    /// Value of the field before the current edit began
+    pub edit_origin: FieldValue,
+
+    /// IDs of items picked with Space
+    pub selected: Vec<String>,
+    /// ID of the item the range being selected in visual mode starts at, so
+    /// it stays put when items are sorted or filtered
+    pub visual_anchor: Option<String>,
+    pub bulk: Option<BulkJob>,
+
+    /// First item shown in the table
+    pub scroll: usize,
+    /// Number of item rows that fit on screen, set when drawing
+    pub viewport_height: usize,
+    /// Keys from the config with the defaults filled in
+    pub bindings: Bindings,
+    /// Styles from the config's theme
+    pub theme: Theme,
+    /// Keys pressed so far of a binding such as `gg`
+    pub pending_keys: Vec<KeyPress>,
+    /// Column widths as last drawn
+    pub drawn_widths: Vec<u16>,
+    /// Where the table was last drawn
+    pub click_areas: ClickAreas,
+    /// Time and place of the last click, to spot double clicks
+    pub last_click: Option<(std::time::Instant, u16, u16)>,
+
+    /// Only items matching this are shown
+    pub filter: Option<Filter>,
+    /// Sort keys, the first one wins
+    pub sort: Vec<Sort>,
+    /// Field the items are grouped by
+    pub group_by: Option<String>,
+    /// Values of the groups folded away
+    pub collapsed: Vec<String>,
+    /// Fields shown by the open view, every field when `None`
+    pub shown_fields: Option<Vec<String>>,
+    /// Name of the open view, `None` for every item
+    pub view: Option<String>,
+    /// Project the views were last set up for, so a newly opened project
+    /// starts in its default view
+    pub views_project: String,
+    /// Items left out by the filter
+    pub hidden_items: Vec<Item>,
+
+    /// Text cut in any editor, for pasting with Ctrl-y
+    pub kill_buffer: String,
+    /// Values entered before, by field ID or prompt name
+    pub input_history: HashMap<String, Vec<String>>,
+}
+
+/// Where parts of the table were last drawn, so mouse clicks can be matched
+/// to them
+#[derive(Debug, Default)]
+pub struct ClickAreas {
+    /// Each column on screen and the field it shows
+    pub columns: Vec<(Rect, usize)>,
+    /// Row the column headers are drawn on
+    pub header_row: u16,
+    /// Open editor, option list or calendar
+    pub popup: Option<Rect>,
+    /// Options scrolled past at the top of the option list
+    pub popup_offset: usize,
+}
+
+fn contains(area: Rect, x: u16, y: u16) -> bool {
+    x >= area.x && x < area.right() && y >= area.y && y < area.bottom()
 }
 
+/// Longest gap between the clicks of a double click
+const DOUBLE_CLICK: std::time::Duration = std::time::Duration::from_millis(400);
+
 #[derive(Debug)]
 pub enum FieldBuffer {
     None,
-    Text(String, u16),
+    Text(LineEditor),
     SingleSelect(Vec<FieldOption>, u16),
-    Date(Date),
-    Iteration(Vec<Iteration>, u16),
+    /// Date picked on the calendar, and any date being typed
+    Date(Date, LineEditor),
+    /// Iterations can't be picked yet
+    Iteration,
 }
 
 impl FieldBuffer {
+    /// Width of the buffer on screen
     pub fn len(&self) -> usize {
         match self {
-            FieldBuffer::Text(text, _) => text.len(),
-            FieldBuffer::Date(_) => 6,
+            FieldBuffer::Text(editor) => editor.width(),
+            FieldBuffer::Date(_, _) => 6,
             _ => 0,
         }
     }
@@ -81,49 +467,713 @@ pub struct UserInfo {
     pub items: Vec<Item>,
     pub fields: Vec<Field>,
     pub projects: Vec<Project>,
+    /// Views set up on GitHub
+    pub views: Vec<View>,
+}
+
+/// The user of the `current` account and the projects of every account
+/// given, each tagged with the account it came from
+pub fn fetch_projects(auths: &[(String, Auth)], current: &str) -> anyhow::Result<(User, Vec<Project>)> {
+    let mut user = None;
+    let mut projects = Vec::new();
+
+    for (account, auth) in auths {
+        let result = github::get_user(auth)
+            .and_then(|u| Ok((github::get_project_ids(auth, &u.login)?, u)));
+
+        match result {
+            Ok((mut found, u)) => {
+                for project in &mut found {
+                    project.account = account.clone();
+                }
+                projects.append(&mut found);
+
+                if account == current {
+                    user = Some(u);
+                }
+            }
+            // Another account failing shouldn't hide the current one's projects
+            Err(err) if account == current => return Err(err),
+            Err(_) => {}
+        }
+    }
+
+    let user = user.ok_or_else(|| anyhow!("Not logged in to {current}"))?;
+    Ok((user, projects))
+}
+
+/// Place of a saved project in the list. Projects that aren't listed, such
+/// as an organization's, are looked up by ID and added to it. `None` only
+/// when GitHub says the project is gone.
+pub fn find_saved_project(
+    auth: &Auth,
+    projects: &mut Vec<Project>,
+    saved: &SavedProject,
+    account: &str,
+) -> anyhow::Result<Option<usize>> {
+    if let Some(index) = projects.iter().position(|p| p.id == saved.id) {
+        return Ok(Some(index));
+    }
+
+    let Some(mut project) = github::get_project(auth, &saved.id)? else {
+        return Ok(None);
+    };
+    project.account = account.to_string();
+    projects.push(project);
+    Ok(Some(projects.len() - 1))
+}
+
+/// Place of a project given as `Owner/Title` or just its title. Projects of
+/// other owners are looked up and added to the list.
+pub fn find_named_project(auth: &Auth, projects: &mut Vec<Project>, name: &str, account: &str) -> anyhow::Result<usize> {
+    let found = match name.split_once('/') {
+        Some((owner, title)) => projects.iter().position(|p| {
+            p.owner.login.eq_ignore_ascii_case(owner) && p.title.to_lowercase() == title.to_lowercase()
+        }),
+        None => projects.iter().position(|p| p.title.to_lowercase() == name.to_lowercase()),
+    };
+    if let Some(index) = found {
+        return Ok(index);
+    }
+
+    let project = match name.split_once('/') {
+        Some((owner, title)) => github::find_project(auth, owner, title)?,
+        None => None,
+    };
+    let mut project = project.ok_or_else(|| anyhow!("No project named \"{name}\""))?;
+    project.account = account.to_string();
+    projects.push(project);
+    Ok(projects.len() - 1)
+}
+
+/// Loads the items and fields of the project at `index`
+pub fn fetch_project(
+    auths: &[(String, Auth)],
+    user: User,
+    projects: Vec<Project>,
+    index: usize,
+) -> anyhow::Result<UserInfo> {
+    let project = match projects.get(index) {
+        Some(project) => project,
+        None if projects.is_empty() => return Err(anyhow!("{} has no projects", user.login)),
+        None => return Err(anyhow!("No project selected")),
+    };
+    let auth = auths
+        .iter()
+        .find(|(account, _)| *account == project.account)
+        .map(|(_, auth)| auth)
+        .ok_or_else(|| anyhow!("Not logged in to {}", project.account))?;
+
+    let items = github::fetch_project_items(auth, &project.id)?;
+    let fields = github::fetch_project_fields(auth, &project.id)?;
+    // Older GitHub Enterprise servers have no views
+    let views = github::fetch_project_views(auth, &project.id).unwrap_or_default();
+
+    Ok(UserInfo {
+        user,
+        projects,
+        items,
+        fields,
+        views,
+    })
 }
 
 impl App {
     pub fn new() -> Self {
+        let mut config: Config = confy::load("projects-tui", "config").unwrap_or_else(|_| Config::default());
+
+        // Columns narrower than this can't be drawn
+        config.max_column_width = config.max_column_width.max(MIN_COLUMN_WIDTH);
+        for column in &mut config.column_widths {
+            column.width = column.width.max(MIN_COLUMN_WIDTH);
+        }
+
+        let (bindings, key_errors) = Bindings::resolve(&config.keymap);
+        let (theme, theme_errors) = Theme::resolve(&config.theme);
+
+        let mut skipped = Vec::new();
+        if !key_errors.is_empty() {
+            skipped.push(format!("Some keys in the config were skipped:\n{}", key_errors.join("\n")));
+        }
+        if !theme_errors.is_empty() {
+            skipped.push(format!("Some theme settings were skipped:\n{}", theme_errors.join("\n")));
+        }
+        let menu_state = match skipped.is_empty() {
+            true => InputMode::Normal,
+            false => InputMode::Error(skipped.join("\n\n")),
+        };
+
         App {
-            config: confy::load("projects-tui", "config").unwrap_or_else(|_| Config::default()),
+            config,
             item_state: 0,
             field_state: 0,
 
-            menu_state: InputMode::Normal,
+            menu_state,
             exit: false,
             reload: false,
             error_hook: Ok(()),
 
             user_info: None,
-            id: None,
+            logins: HashMap::new(),
+            relogin: false,
+            open_project: None,
+            utc_offset: UtcOffset::UTC,
 
             input: FieldBuffer::None,
+
+            history: History::default(),
+            edit_origin: FieldValue::Empty,
+
+            selected: Vec::new(),
+            visual_anchor: None,
+            bulk: None,
+
+            scroll: 0,
+            viewport_height: 1,
+            bindings,
+            theme,
+            pending_keys: Vec::new(),
+            drawn_widths: Vec::new(),
+
+            click_areas: ClickAreas::default(),
+            last_click: None,
+
+            filter: None,
+            sort: Vec::new(),
+            group_by: None,
+            collapsed: Vec::new(),
+            shown_fields: None,
+            view: None,
+            views_project: String::new(),
+            hidden_items: Vec::new(),
+
+            kill_buffer: String::new(),
+            input_history: HashMap::new(),
         }
     }
 
-    pub fn load_info(&self) -> anyhow::Result<UserInfo> {
-        if let Some(cred) = &self.id {
-            let user = github::get_user(&cred.token)?;
-            let projects = github::get_project_ids(&cred.token, &user.login)?;
-            let items =
-                github::fetch_project_items(&cred.token, &projects[self.config.project_state].id)?;
-            let fields =
-                github::fetch_project_fields(&cred.token, &projects[self.config.project_state].id)?;
+    /// Loads the projects, and the items and fields of the open one. That is
+    /// the one given with `--project`, or else the account's last project.
+    /// When that is gone the first project is opened instead.
+    pub fn reload_info(&mut self) -> anyhow::Result<()> {
+        let account = self.config.account();
+        let auths = self.auths();
+        let auth = self.auth_for(&account.name)?;
+        let (user, mut projects) = fetch_projects(&auths, &account.name)?;
+
+        let mut missing = None;
+        if let Some(name) = self.open_project.take() {
+            self.config.project_state = find_named_project(&auth, &mut projects, &name, &account.name)?;
+        } else if let Some(saved) = &account.project {
+            match find_saved_project(&auth, &mut projects, saved, &account.name)? {
+                Some(index) => self.config.project_state = index,
+                None => {
+                    self.config.project_state = 0;
+                    missing = Some(format!("{}/{}", saved.owner, saved.title));
+                }
+            }
+        } else if self.config.project_state >= projects.len() {
+            self.config.project_state = 0;
+        }
+
+        let info = fetch_project(&auths, user, projects, self.config.project_state)?;
+        let opened = info.projects[self.config.project_state].clone();
+        self.set_info(info);
 
-            return Ok(UserInfo {
-                user,
-                projects,
-                items,
-                fields,
-            });
+        if let Some(missing) = missing {
+            self.menu_state = InputMode::Error(format!(
+                "The project {missing} no longer exists, opened {} instead",
+                opened.full_name()
+            ));
+        }
+        self.config.remember_project(&opened);
+        self.save_config();
+        Ok(())
+    }
+
+    /// Replaces the loaded project, keeping the current filter and sort. A
+    /// newly opened project starts in its default view instead.
+    pub fn set_info(&mut self, info: UserInfo) {
+        self.user_info = Some(info);
+        self.hidden_items.clear();
+
+        let project_id = self.project_id().unwrap_or_default();
+        if project_id != self.views_project {
+            self.views_project = project_id.clone();
+            let default = self.config.default_view(&project_id).map(String::from);
+
+            if let Err(err) = self.open_view(default.as_deref()) {
+                self.menu_state = InputMode::Error(err.to_string());
+            }
+        } else {
+            self.apply_view();
         }
 
-        Err(anyhow!("No user credential loaded"))
+        if self.is_field_hidden(self.field_state) {
+            self.right();
+        }
     }
 
-    pub fn reload_info(&mut self) -> anyhow::Result<()> {
-        self.user_info = Some(self.load_info()?);
+    /// Views of the open project, GitHub's first and then the local ones
+    pub fn views(&self) -> Vec<View> {
+        let mut views = self.info().map(|info| info.views.clone()).unwrap_or_default();
+
+        if let Some(saved) = self.project_id().ok().and_then(|id| self.config.project_views(&id).cloned()) {
+            views.extend(saved.views.into_iter().map(|view| View { local: true, ..view }));
+        }
+
+        views
+    }
+
+    /// Switches to a view by name, `None` shows every item unsorted
+    pub fn open_view(&mut self, name: Option<&str>) -> anyhow::Result<()> {
+        let view = match name {
+            Some(name) => Some(
+                self.views()
+                    .into_iter()
+                    .find(|v| v.name == name)
+                    .ok_or_else(|| anyhow!("No view named \"{name}\""))?,
+            ),
+            None => None,
+        };
+        self.view = view.as_ref().map(|v| v.name.clone());
+        let view = view.unwrap_or_default();
+
+        // Unsorted means the order GitHub gives, so fetch it again
+        if !self.sort.is_empty() && view.sort.is_empty() {
+            self.reload = true;
+        }
+        self.sort = view.sort;
+        self.group_by = view.group_by;
+        self.collapsed.clear();
+
+        // Fields are left alone when the view names none of them
+        let fields = self.info().map(|info| info.fields.clone()).unwrap_or_default();
+        self.shown_fields = Some(view.fields).filter(|shown| {
+            fields
+                .iter()
+                .any(|f| shown.iter().any(|s| s.eq_ignore_ascii_case(f.get_name())))
+        });
+        if self.is_field_hidden(self.field_state) {
+            self.right();
+        }
+
+        self.set_filter(&view.filter)
+    }
+
+    /// Moves through the views, with every item before the first one
+    pub fn cycle_view(&mut self, step: isize) -> anyhow::Result<()> {
+        let names: Vec<Option<String>> = std::iter::once(None)
+            .chain(self.views().into_iter().map(|v| Some(v.name)))
+            .collect();
+
+        let current = names.iter().position(|n| *n == self.view).unwrap_or(0) as isize;
+        let next = (current + step).rem_euclid(names.len() as isize) as usize;
+
+        self.open_view(names[next].clone().as_deref())
+    }
+
+    /// Saves the filter, sort, grouping and shown fields as a local view
+    pub fn save_view(&mut self, name: &str) -> anyhow::Result<()> {
+        let project_id = self.project_id()?;
+        let field_count = self.info()?.fields.len();
+
+        let shown: Vec<String> = (0..field_count)
+            .filter(|i| !self.is_field_hidden(*i))
+            .map(|i| self.info().map(|info| info.fields[i].get_name().to_string()))
+            .collect::<anyhow::Result<_>>()?;
+
+        let view = View {
+            name: name.to_string(),
+            filter: self.filter.as_ref().map_or("", |f| f.query()).to_string(),
+            sort: self.sort.clone(),
+            group_by: self.group_by.clone(),
+            // Nothing hidden means every field, including ones added later
+            fields: if shown.len() == field_count { Vec::new() } else { shown },
+            ..Default::default()
+        };
+
+        self.config.save_view(&project_id, view);
+        self.view = Some(name.to_string());
+        self.save_config();
+        Ok(())
+    }
+
+    /// Makes the open view the one the project opens with
+    pub fn set_default_view(&mut self) -> anyhow::Result<()> {
+        let project_id = self.project_id()?;
+        self.config.set_default_view(&project_id, self.view.clone());
+        self.save_config();
+
+        self.menu_state = InputMode::Message(match &self.view {
+            Some(name) => format!("The project now opens with the view {name}"),
+            None => String::from("The project now opens with every item"),
+        });
+        Ok(())
+    }
+
+    /// Sorts and filters the loaded items, keeping the cursor on the same item
+    pub fn apply_view(&mut self) {
+        let current = self
+            .info()
+            .ok()
+            .and_then(|info| info.items.get(self.item_state))
+            .map(|item| item.id.clone());
+
+        let Some(info) = &mut self.user_info else {
+            return;
+        };
+
+        info.items.append(&mut self.hidden_items);
+
+        if let Some(filter) = &self.filter {
+            let (shown, hidden) = info
+                .items
+                .drain(..)
+                .partition(|item| filter.matches(item, &info.fields));
+            info.items = shown;
+            self.hidden_items = hidden;
+        }
+
+        sort::apply_all(&self.sort, &mut info.items);
+
+        let group_field = self
+            .group_by
+            .as_ref()
+            .and_then(|name| info.fields.iter().find(|f| f.get_name().eq_ignore_ascii_case(name)));
+        if let Some(field) = group_field {
+            group::sort_into_groups(field, &mut info.items);
+        }
+
+        self.item_state = current
+            .and_then(|id| info.items.iter().position(|item| item.id == id))
+            .unwrap_or(0);
+    }
+
+    /// Shows only items matching a query, an empty query shows every item
+    pub fn set_filter(&mut self, query: &str) -> anyhow::Result<()> {
+        let login = self.info()?.user.login.clone();
+        self.filter = Some(Filter::parse(query).with_user(&login)).filter(|_| !query.trim().is_empty());
+        self.apply_view();
+
+        // An empty table has no cursor, so keep the items instead
+        if self.info()?.items.is_empty() && !self.hidden_items.is_empty() {
+            self.filter = None;
+            self.apply_view();
+            return Err(anyhow!("No items match \"{query}\""));
+        }
+
+        Ok(())
+    }
+
+    /// Sorts by the current field, then reverses, then stops sorting
+    pub fn cycle_sort(&mut self) -> anyhow::Result<()> {
+        let field = self.info()?.fields[self.field_state].get_name().to_string();
+
+        self.sort = match self.sort.first() {
+            Some(sort) if sort.field == field && !sort.descending => vec![Sort {
+                field,
+                descending: true,
+            }],
+            Some(sort) if sort.field == field => Vec::new(),
+            _ => vec![Sort {
+                field,
+                descending: false,
+            }],
+        };
+
+        // Unsorted means the order GitHub gives, so fetch it again
+        if self.sort.is_empty() {
+            self.reload = true;
+        }
+
+        self.apply_view();
+        Ok(())
+    }
+
+    /// Groups by the current field, or stops grouping when it already is
+    pub fn cycle_group(&mut self) -> anyhow::Result<()> {
+        let field = &self.info()?.fields[self.field_state];
+
+        if !group::GROUPABLE.contains(&field.get_type()) {
+            return Err(anyhow!(
+                "Can't group by {}, only by single select, iteration, text or assignee fields",
+                field.get_name()
+            ));
+        }
+
+        let name = field.get_name().to_string();
+        self.group_by = match &self.group_by {
+            Some(group) if group.eq_ignore_ascii_case(&name) => None,
+            _ => Some(name),
+        };
+        self.collapsed.clear();
+
+        // Ungrouped items go back to their sorted or GitHub order
+        if self.group_by.is_none() && self.sort.is_empty() {
+            self.reload = true;
+        }
+
+        self.apply_view();
+        Ok(())
+    }
+
+    /// Field the items are grouped by, if it is in the project
+    pub fn group_field(&self) -> Option<&Field> {
+        let name = self.group_by.as_ref()?;
+        self.info()
+            .ok()?
+            .fields
+            .iter()
+            .find(|f| f.get_name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn groups(&self) -> Vec<Group> {
+        match (self.group_field(), self.info()) {
+            (Some(field), Ok(info)) => group::groups(field, &info.items),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Rows of the table, with group headers when grouping
+    pub fn rows(&self) -> Vec<TableRow> {
+        match self.group_field() {
+            Some(_) => group::rows(&self.groups(), &self.collapsed),
+            None => (0..self.info().map_or(0, |info| info.items.len())).map(TableRow::Item).collect(),
+        }
+    }
+
+    /// Items not in a collapsed group, in table order
+    fn visible_items(&self) -> Vec<usize> {
+        self.rows()
+            .into_iter()
+            .filter_map(|row| match row {
+                TableRow::Item(item) => Some(item),
+                TableRow::Group(_) => None,
+            })
+            .collect()
+    }
+
+    /// Row the cursor is on, the header of its group when that is collapsed
+    pub fn cursor_line(&self) -> usize {
+        let rows = self.rows();
+        let groups = self.groups();
+
+        rows.iter()
+            .position(|row| *row == TableRow::Item(self.item_state))
+            .or_else(|| {
+                let group = groups.iter().position(|g| g.items.contains(&self.item_state))?;
+                rows.iter().position(|row| *row == TableRow::Group(group))
+            })
+            .unwrap_or(self.item_state)
+    }
+
+    /// Folds or unfolds the group the cursor is in
+    pub fn toggle_group(&mut self) {
+        let Some(group) = self.groups().into_iter().find(|g| g.items.contains(&self.item_state)) else {
+            return;
+        };
+
+        match self.collapsed.iter().position(|v| *v == group.value) {
+            Some(index) => {
+                self.collapsed.remove(index);
+            }
+            None => self.collapsed.push(group.value),
+        }
+    }
+
+    pub fn collapse_groups(&mut self) {
+        self.collapsed = self.groups().into_iter().map(|g| g.value).collect();
+    }
+
+    pub fn expand_groups(&mut self) {
+        self.collapsed.clear();
+    }
+
+    /// Value of the cursor's group to give an item added there. Assignees and
+    /// iterations can't be set here, and items without a value need none.
+    fn group_value_for_new_item(&self) -> Option<(String, FieldValue)> {
+        let field = self.group_field()?;
+        if !field.is_editable() {
+            return None;
+        }
+
+        let item = self.info().ok()?.items.get(self.item_state)?;
+        let value = FieldValue::from_item_field(item.field_values.get_from_field(field.get_name()));
+
+        (value != FieldValue::Empty).then(|| (field.get_id().to_string(), value))
+    }
+
+    pub fn is_field_hidden(&self, index: usize) -> bool {
+        match (self.project_id(), self.info()) {
+            (Ok(project_id), Ok(info)) => info.fields.get(index).is_some_and(|f| {
+                let outside_view = self
+                    .shown_fields
+                    .as_ref()
+                    .is_some_and(|shown| !shown.iter().any(|s| s.eq_ignore_ascii_case(f.get_name())));
+
+                outside_view || self.config.is_field_hidden(&project_id, f.get_name())
+            }),
+            _ => false,
+        }
+    }
+
+    pub fn hide_field(&mut self) -> anyhow::Result<()> {
+        let project_id = self.project_id()?;
+        let fields = &self.info()?.fields;
+
+        if (0..fields.len()).filter(|i| !self.is_field_hidden(*i)).count() <= 1 {
+            return Err(anyhow!("Can't hide the last field"));
+        }
+
+        let field = fields[self.field_state].get_name().to_string();
+        self.config.hide_field(&project_id, &field);
+        self.save_config();
+        self.right();
+        Ok(())
+    }
+
+    pub fn show_all_fields(&mut self) -> anyhow::Result<()> {
+        self.shown_fields = None;
+        self.config.show_all_fields(&self.project_id()?);
+        self.save_config();
+        Ok(())
+    }
+
+    /// Opens the current issue or pull request in the browser
+    pub fn open_item(&self) -> anyhow::Result<()> {
+        let item = &self.info()?.items[self.item_state];
+        let url = item.url().ok_or_else(|| anyhow!("Draft issues have no page to open"))?;
+
+        let opener = if cfg!(target_os = "macos") {
+            "open"
+        } else if cfg!(windows) {
+            "explorer"
+        } else {
+            "xdg-open"
+        };
+
+        std::process::Command::new(opener)
+            .arg(url)
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .map_err(|err| anyhow!("Couldn't open {url}: {err}"))?;
+
+        Ok(())
+    }
+
+    /// Adds a key to any pressed before it, giving the action once they
+    /// make up a binding
+    pub fn key_action(&mut self, mode: Mode, key: KeyEvent) -> Option<Action> {
+        self.pending_keys.push(KeyPress::from_event(key));
+
+        match self.bindings.lookup(mode, &self.pending_keys) {
+            Lookup::Action(action) => {
+                self.pending_keys.clear();
+                Some(action)
+            }
+            Lookup::Pending => None,
+            Lookup::None => {
+                // Start over from this key, so `g` then `j` still moves down
+                let retry = self.pending_keys.len() > 1;
+                self.pending_keys.clear();
+
+                if retry {
+                    self.key_action(mode, key)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    pub fn perform(&mut self, action: Action) -> anyhow::Result<()> {
+        match action {
+            Action::Up => self.previous(),
+            Action::Down => self.next(),
+            Action::Left => self.left(),
+            Action::Right => self.right(),
+            Action::Top => self.jump_to_start(),
+            Action::Bottom => self.jump_to_end(),
+            Action::PageDown => self.jump(self.viewport_height as isize),
+            Action::PageUp => self.jump(-(self.viewport_height as isize)),
+            Action::HalfPageDown => self.jump(self.half_page()),
+            Action::HalfPageUp => self.jump(-self.half_page()),
+
+            Action::Edit => self.begin_editing()?,
+            Action::AddItem => self.menu_state = InputMode::AddItem(self.new_editor("", "add item")),
+            Action::ClearField => self.clear_field()?,
+            Action::DeleteItem => self.menu_state = InputMode::Confirm(Action::DeleteItem),
+            Action::ArchiveItem if !self.selected_ids().is_empty() => {
+                self.menu_state = InputMode::Confirm(Action::ArchiveItem)
+            }
+            Action::ArchiveItem => self.archive_item()?,
+            Action::OpenItem => self.open_item()?,
+            Action::Undo => self.undo()?,
+            Action::Redo => self.redo()?,
+
+            Action::ToggleSelect => self.toggle_selected()?,
+            Action::VisualSelect => self.toggle_visual(),
+            Action::ClearSelection => self.clear_selection(),
+
+            Action::Filter => {
+                let query = self.filter.as_ref().map_or("", |f| f.query()).to_string();
+                self.menu_state = InputMode::Filter(self.new_editor(&query, "filter"));
+            }
+            Action::ClearFilter => self.set_filter("")?,
+            Action::SortBy => self.cycle_sort()?,
+            Action::ClearSort => {
+                self.sort.clear();
+                self.reload = true;
+            }
+            Action::HideField => self.hide_field()?,
+            Action::ShowAllFields => self.show_all_fields()?,
+
+            Action::WidenColumn => self.resize_column(1)?,
+            Action::NarrowColumn => self.resize_column(-1)?,
+            Action::ResetColumnWidth => self.reset_column_width()?,
+            Action::FitToTerminal => self.toggle_fit_to_terminal(),
+            Action::ToggleFooter => self.toggle_footer(),
+
+            Action::GroupBy => self.cycle_group()?,
+            Action::ToggleGroup => self.toggle_group(),
+            Action::CollapseGroups => self.collapse_groups(),
+            Action::ExpandGroups => self.expand_groups(),
+            Action::NextView => self.cycle_view(1)?,
+            Action::PreviousView => self.cycle_view(-1)?,
+            Action::SaveView => {
+                // A local view is saved over by default
+                let name = self.view.clone().filter(|name| self.views().iter().any(|v| v.local && v.name == *name));
+                self.menu_state = InputMode::SaveView(self.new_editor(&name.unwrap_or_default(), "view"));
+            }
+            Action::DefaultView => self.set_default_view()?,
+
+            Action::SwitchProject => self.menu_state = InputMode::SwitchProject(self.config.project_state),
+            Action::SwitchAccount => {
+                let current = self.config.account().name;
+                let index = self.config.accounts().iter().position(|a| a.name == current);
+                self.menu_state = InputMode::SwitchAccount(index.unwrap_or(0));
+            }
+            Action::Export => {
+                let path = format!("{}.csv", self.info()?.projects[self.config.project_state].title);
+                self.menu_state = InputMode::Export(self.new_editor(&path, "export"));
+            }
+            Action::CommandPalette => self.menu_state = InputMode::Palette(LineEditor::new(""), 0),
+            Action::Insights => self.menu_state = InputMode::Insights(false),
+            Action::Help => self.menu_state = InputMode::Help(0),
+            Action::Quit => self.exit = true,
+
+            // Only bound in lists, the calendar and prompts
+            Action::Confirm
+            | Action::Cancel
+            | Action::NextMonth
+            | Action::PreviousMonth
+            | Action::NextYear
+            | Action::PreviousYear
+            | Action::Today => {}
+        }
+
         Ok(())
     }
 
@@ -139,40 +1189,251 @@ impl App {
             .ok_or_else(|| anyhow!("No user info loaded"))
     }
 
+    /// Token and host of an account logged in to
+    pub fn auth_for(&self, account: &str) -> anyhow::Result<Auth> {
+        let login = self
+            .logins
+            .get(account)
+            .ok_or_else(|| anyhow!("Not logged in to {account}"))?;
+        let host = self
+            .config
+            .accounts()
+            .into_iter()
+            .find(|a| a.name == account)
+            .map_or_else(default_host, |a| a.host);
+
+        Ok(Auth {
+            token: login.credential.token.clone(),
+            host,
+        })
+    }
+
+    /// Token for the open project, which may belong to another account when
+    /// projects are merged
+    pub fn auth(&self) -> anyhow::Result<Auth> {
+        let account = match self.info().ok().and_then(|i| i.projects.get(self.config.project_state)) {
+            Some(project) => project.account.clone(),
+            None => self.config.account().name,
+        };
+
+        self.auth_for(&account)
+    }
+
+    /// Token from outside the app, which only stands in for the first
+    /// account since it can't be told apart from the others
+    pub fn external_login(&self, account: &Account, token_file: Option<&str>) -> anyhow::Result<Option<Login>> {
+        match account.name == self.config.accounts()[0].name {
+            true => credentials::find_token(token_file, &account.host),
+            false => Ok(None),
+        }
+    }
+
+    /// Logs in to the other accounts without asking, so their projects can
+    /// be merged. Encrypted logins need the passphrase in the environment.
+    pub fn add_saved_logins(&mut self, token_file: Option<&str>) {
+        for account in self.config.accounts() {
+            if self.logins.contains_key(&account.name) {
+                continue;
+            }
+
+            let login = match self.external_login(&account, token_file) {
+                Ok(Some(login)) => Some(login),
+                _ => match credentials::load(&account.name, self.config.encrypt_credentials) {
+                    Ok(Some(Saved::Plain(credential))) => Some(credential),
+                    Ok(Some(Saved::Locked(locked))) => {
+                        credentials::env_passphrase().and_then(|p| locked.unlock(&p).ok())
+                    }
+                    _ => None,
+                }
+                .map(|credential| Login {
+                    credential,
+                    source: TokenSource::Saved,
+                }),
+            };
+
+            if let Some(login) = login {
+                self.logins.insert(account.name, login);
+            }
+        }
+    }
+
+    /// Accounts to list projects from, only the current one unless merged
+    pub fn auths(&self) -> Vec<(String, Auth)> {
+        let current = self.config.account().name;
+
+        self.config
+            .accounts()
+            .into_iter()
+            .filter(|a| self.config.merge_accounts || a.name == current)
+            .filter_map(|a| Some((a.name.clone(), self.auth_for(&a.name).ok()?)))
+            .collect()
+    }
+
+    pub fn project_id(&self) -> anyhow::Result<String> {
+        Ok(self.info()?.projects[self.config.project_state].id.clone())
+    }
+
+    /// Moves to the next shown field, wrapping around
     pub fn right(&mut self) {
         if let Some(info) = &self.user_info {
-            self.field_state += 1;
-            if self.field_state >= info.fields.len() {
-                self.field_state = 0;
+            for _ in 0..info.fields.len() {
+                self.field_state += 1;
+                if self.field_state >= info.fields.len() {
+                    self.field_state = 0;
+                }
+
+                if !self.is_field_hidden(self.field_state) {
+                    break;
+                }
             }
         }
     }
 
     pub fn left(&mut self) {
         if let Some(info) = &self.user_info {
-            self.field_state = match self.field_state {
-                0 => info.fields.len() - 1,
-                _ => self.field_state - 1,
-            };
+            for _ in 0..info.fields.len() {
+                self.field_state = match self.field_state {
+                    0 => info.fields.len() - 1,
+                    _ => self.field_state - 1,
+                };
+
+                if !self.is_field_hidden(self.field_state) {
+                    break;
+                }
+            }
         }
     }
 
+    /// Place of the cursor among the items that can be moved to, or of the
+    /// first one after it when its group is collapsed
+    fn visible_position(&self, visible: &[usize]) -> usize {
+        visible
+            .iter()
+            .position(|i| *i >= self.item_state)
+            .unwrap_or(visible.len().saturating_sub(1))
+    }
+
+    /// Items in collapsed groups are skipped over
     pub fn next(&mut self) {
-        if let Some(info) = &self.user_info {
-            self.item_state += 1;
-            if self.item_state >= info.items.len() {
-                self.item_state = 0;
-            }
+        let visible = self.visible_items();
+        if visible.is_empty() {
+            return;
         }
+
+        let position = match visible.iter().position(|i| *i == self.item_state) {
+            Some(position) => (position + 1) % visible.len(),
+            None => self.visible_position(&visible),
+        };
+        self.item_state = visible[position];
     }
 
     pub fn previous(&mut self) {
-        if let Some(info) = &self.user_info {
-            self.item_state = match self.item_state {
-                0 => info.items.len() - 1,
-                _ => self.item_state - 1,
-            };
+        let visible = self.visible_items();
+        if visible.is_empty() {
+            return;
         }
+
+        let position = match visible.iter().position(|i| *i == self.item_state) {
+            Some(0) => visible.len() - 1,
+            Some(position) => position - 1,
+            None => self.visible_position(&visible).saturating_sub(1),
+        };
+        self.item_state = visible[position];
+    }
+
+    /// Moves the selected item by a number of rows, stopping at either end
+    pub fn jump(&mut self, rows: isize) {
+        let visible = self.visible_items();
+        if visible.is_empty() {
+            return;
+        }
+
+        let last = visible.len() as isize - 1;
+        let position = (self.visible_position(&visible) as isize + rows).clamp(0, last);
+        self.item_state = visible[position as usize];
+    }
+
+    pub fn jump_to_start(&mut self) {
+        if let Some(first) = self.visible_items().first() {
+            self.item_state = *first;
+        }
+    }
+
+    pub fn jump_to_end(&mut self) {
+        if let Some(last) = self.visible_items().last() {
+            self.item_state = *last;
+        }
+    }
+
+    pub fn half_page(&self) -> isize {
+        (self.viewport_height / 2).max(1) as isize
+    }
+
+    /// Scrolls the table so the selected item stays within `height` rows
+    pub fn update_scroll(&mut self, height: usize) {
+        self.viewport_height = height.max(1);
+        let line = self.cursor_line();
+
+        if line < self.scroll {
+            self.scroll = line;
+        } else if line >= self.scroll + self.viewport_height {
+            self.scroll = line + 1 - self.viewport_height;
+        }
+
+        // Don't leave blank rows at the bottom after items are removed
+        self.scroll = self.scroll.min(self.rows().len().saturating_sub(self.viewport_height));
+    }
+
+    /// Row of the selected item relative to the top of the table
+    pub fn cursor_row(&self) -> u16 {
+        self.cursor_line().saturating_sub(self.scroll) as u16
+    }
+
+    /// Grows or shrinks the current column and remembers its new width
+    pub fn resize_column(&mut self, delta: i16) -> anyhow::Result<()> {
+        let project_id = self.project_id()?;
+        let field = self.info()?.fields[self.field_state].get_name().to_string();
+        let current = self.drawn_widths.get(self.field_state).copied().unwrap_or(MIN_COLUMN_WIDTH);
+        let width = (current as i16 + delta).max(MIN_COLUMN_WIDTH as i16) as u16;
+
+        self.config.set_column_width(&project_id, &field, Some(width));
+        self.save_config();
+        Ok(())
+    }
+
+    pub fn reset_column_width(&mut self) -> anyhow::Result<()> {
+        let project_id = self.project_id()?;
+        let field = self.info()?.fields[self.field_state].get_name().to_string();
+
+        self.config.set_column_width(&project_id, &field, None);
+        self.save_config();
+        Ok(())
+    }
+
+    pub fn toggle_fit_to_terminal(&mut self) {
+        self.config.fit_to_terminal = !self.config.fit_to_terminal;
+        self.save_config();
+    }
+
+    pub fn toggle_footer(&mut self) {
+        self.config.footer = !self.config.footer;
+        self.save_config();
+    }
+
+    /// Items the footer adds up, those of the cursor's group when grouping
+    pub fn footer_items(&self) -> Vec<&Item> {
+        let Ok(info) = self.info() else {
+            return Vec::new();
+        };
+
+        match self.groups().into_iter().find(|g| g.items.contains(&self.item_state)) {
+            Some(group) => info.items[group.items].iter().collect(),
+            None => info.items.iter().collect(),
+        }
+    }
+
+    pub fn save_config(&self) {
+        let _ = confy::store("projects-tui", "config", &self.config);
     }
 
     pub fn shift_option_up(&mut self) {
@@ -202,26 +1463,185 @@ impl App {
                 } - 1;
             }
         }
-    }
+    }
+
+    pub fn shift_project_down(&mut self) {
+        if let Some(app_info) = &self.user_info {
+            if let InputMode::SwitchProject(ref mut selected) = &mut self.menu_state {
+                *selected += 1;
+                if *selected >= app_info.projects.len() {
+                    *selected = 0;
+                }
+            }
+        }
+    }
+
+    pub fn select_project(&mut self) {
+        if let InputMode::SwitchProject(selected) = self.menu_state {
+            // Merged projects can belong to another account, which becomes
+            // the current one
+            if let Some(project) = self.info().ok().and_then(|i| i.projects.get(selected)).cloned() {
+                self.config.remember_project(&project);
+                self.config.account = project.account;
+            }
+
+            self.config.project_state = selected;
+            self.menu_state = InputMode::LoadingProject;
+            self.filter = None;
+            self.sort.clear();
+            self.reload = true;
+            self.save_config();
+        }
+    }
+
+    pub fn shift_account_up(&mut self) {
+        let count = self.config.accounts().len();
+        if let InputMode::SwitchAccount(ref mut selected) = self.menu_state {
+            *selected = (*selected + count - 1) % count;
+        }
+    }
+
+    pub fn shift_account_down(&mut self) {
+        let count = self.config.accounts().len();
+        if let InputMode::SwitchAccount(ref mut selected) = self.menu_state {
+            *selected = (*selected + 1) % count;
+        }
+    }
+
+    /// Switches to the picked account, closing the editor so it can be
+    /// logged in to before its last project is loaded
+    pub fn select_account(&mut self) {
+        if let InputMode::SwitchAccount(selected) = self.menu_state {
+            let Some(account) = self.config.accounts().into_iter().nth(selected) else {
+                return;
+            };
+
+            if account.name == self.config.account().name {
+                self.menu_state = InputMode::Normal;
+                return;
+            }
+
+            self.config.account = account.name;
+            self.menu_state = InputMode::LoadingProject;
+            self.filter = None;
+            self.sort.clear();
+            self.relogin = true;
+            self.save_config();
+        }
+    }
+
+    /// Lists the projects of every account together, or only the current
+    /// account's
+    pub fn toggle_merge_accounts(&mut self) {
+        self.config.merge_accounts = !self.config.merge_accounts;
+
+        // The open project keeps its place by being remembered
+        if let Some(project) = self.info().ok().and_then(|i| i.projects.get(self.config.project_state)).cloned() {
+            self.config.remember_project(&project);
+        }
+
+        self.menu_state = InputMode::LoadingProject;
+        self.relogin = true;
+        self.save_config();
+    }
+
+    /// Records a click, returning whether it makes a double click
+    pub fn register_click(&mut self, x: u16, y: u16) -> bool {
+        let now = std::time::Instant::now();
+        let double = self
+            .last_click
+            .is_some_and(|(time, last_x, last_y)| (last_x, last_y) == (x, y) && now - time < DOUBLE_CLICK);
+
+        // A third click starts over instead of making another double click
+        self.last_click = if double { None } else { Some((now, x, y)) };
+        double
+    }
+
+    /// Selects the cell under a click, or only the column for a header.
+    /// Returns whether an item was clicked.
+    pub fn click_cell(&mut self, x: u16, y: u16) -> bool {
+        let Some(&(area, field)) = self
+            .click_areas
+            .columns
+            .iter()
+            .find(|(area, _)| x >= area.x && x < area.right())
+        else {
+            return false;
+        };
+
+        if y == self.click_areas.header_row {
+            self.field_state = field;
+            return false;
+        }
+
+        if !contains(area, x, y) {
+            return false;
+        }
+
+        // Clicking a group header folds or unfolds it
+        let item = match self.rows().get(self.scroll + (y - area.y) as usize) {
+            Some(TableRow::Item(item)) => *item,
+            Some(TableRow::Group(group)) => {
+                if let Some(group) = self.groups().get(*group) {
+                    self.item_state = group.items.start;
+                    self.toggle_group();
+                }
+                return false;
+            }
+            None => return false,
+        };
+
+        self.item_state = item;
+        self.field_state = field;
+        true
+    }
+
+    /// Picks an option or day with a click, returning whether the click was
+    /// on the open editor at all. Clicking the day already picked saves it.
+    pub fn click_popup(&mut self, x: u16, y: u16) -> anyhow::Result<bool> {
+        let Some(area) = self.click_areas.popup else {
+            return Ok(false);
+        };
+
+        if !contains(area, x, y) {
+            return Ok(false);
+        }
 
-    pub fn shift_project_down(&mut self) {
-        if let Some(app_info) = &self.user_info {
-            if let InputMode::SwitchProject(ref mut selected) = &mut self.menu_state {
-                *selected += 1;
-                if *selected >= app_info.projects.len() {
-                    *selected = 0;
+        let save = match &mut self.input {
+            // The list only has borders on its sides
+            FieldBuffer::SingleSelect(options, index) => {
+                let row = (y - area.y) as usize + self.click_areas.popup_offset;
+                if row >= options.len() {
+                    return Ok(true);
                 }
+
+                *index = row as u16;
+                true
             }
-        }
-    }
 
-    pub fn select_project(&mut self) {
-        if let InputMode::SwitchProject(selected) = self.menu_state {
-            self.config.project_state = selected;
-            self.menu_state = InputMode::LoadingProject;
-            self.reload = true;
-            confy::store("projects-tui", "config", &self.config);
+            // Days are counted from inside the calendar's border
+            FieldBuffer::Date(date, entry) if x > area.x && y > area.y => {
+                match dates::calendar_date_at(*date, x - area.x - 1, y - area.y - 1) {
+                    Some(day) if day == *date => true,
+                    Some(day) => {
+                        *date = day;
+                        *entry = LineEditor::new("");
+                        false
+                    }
+                    None => false,
+                }
+            }
+
+            _ => false,
+        };
+
+        if save {
+            self.save_field()?;
+            self.menu_state = InputMode::Normal;
+            self.input = FieldBuffer::None;
         }
+
+        Ok(true)
     }
 
     pub fn begin_editing(&mut self) -> anyhow::Result<()> {
@@ -250,6 +1670,8 @@ impl App {
                 _ => {}
             }
 
+            self.edit_origin = FieldValue::from_item_field(field_value);
+
             // Empty item field
             if let ProjectV2ItemField::Empty(_v) = field_value {
                 info.items[self.item_state]
@@ -261,6 +1683,14 @@ impl App {
                     .get_from_field(&info.fields[self.field_state].get_name());
             }
 
+            let vim = self.config.vim_editor;
+            let history = self
+                .input_history
+                .get(info.fields[self.field_state].get_id())
+                .cloned()
+                .unwrap_or_default();
+            let new_editor = |text: &str| LineEditor::new(text).with_vim(vim).with_history(history.clone());
+
             self.input = match field_value {
                 ProjectV2ItemField::SingleSelectValue { name: _, field } => {
                     FieldBuffer::SingleSelect(
@@ -273,28 +1703,21 @@ impl App {
                     )
                 }
 
-                ProjectV2ItemField::IterationValue {
-                    duration: _,
-                    title: _,
-                    field,
-                } => FieldBuffer::Iteration(
-                    field.configuration.iterations.clone(),
-                    field
-                        .configuration
-                        .iterations
-                        .iter()
-                        .position(|x| x.title == field_value.value())
-                        .unwrap() as u16,
-                ),
+                ProjectV2ItemField::IterationValue { .. } => FieldBuffer::Iteration,
 
-                ProjectV2ItemField::TextValue { text, field: _ } => {
-                    FieldBuffer::Text(text.clone(), text.len() as u16)
-                }
+                ProjectV2ItemField::TextValue { text, field: _ } => FieldBuffer::Text(new_editor(text)),
 
-                ProjectV2ItemField::DateValue { date, field: _ } => {
-                    let format = format_description::parse("[year]-[month]-[day]")?;
-                    FieldBuffer::Date(Date::parse(date, &format)?)
-                }
+                // An empty field starts blank rather than at the default of zero
+                ProjectV2ItemField::NumberValue { number, field: _ } => match self.edit_origin {
+                    FieldValue::Empty => FieldBuffer::Text(new_editor("")),
+                    _ => FieldBuffer::Text(new_editor(&number.to_string())),
+                },
+
+                // An empty field starts at today
+                ProjectV2ItemField::DateValue { date, field: _ } => match self.edit_origin {
+                    FieldValue::Empty => FieldBuffer::Date(dates::today(self.utc_offset), LineEditor::new("")),
+                    _ => FieldBuffer::Date(Date::parse(date, dates::FORMAT)?, LineEditor::new("")),
+                },
 
                 _ => FieldBuffer::None,
             };
@@ -305,44 +1728,83 @@ impl App {
         Ok(())
     }
 
-    pub fn backspace(&mut self) {
-        if let FieldBuffer::Text(ref mut text, ref mut cursor_pos) = self.input {
-            if *cursor_pos == 0 {
-                return;
-            }
+    /// Editor for a value, with the values entered under `history_key` before
+    pub fn new_editor(&self, text: &str, history_key: &str) -> LineEditor {
+        LineEditor::new(text)
+            .with_vim(self.config.vim_editor)
+            .with_history(self.input_history.get(history_key).cloned().unwrap_or_default())
+    }
 
-            *cursor_pos -= 1;
-            text.remove(*cursor_pos as usize);
+    /// Adds a value to the history kept for an editor
+    pub fn remember_input(&mut self, history_key: &str, value: &str) {
+        let history = self.input_history.entry(history_key.to_string()).or_default();
+
+        if value.is_empty() || history.last().is_some_and(|last| last == value) {
+            return;
+        }
+
+        history.push(value.to_string());
+        if history.len() > 50 {
+            history.remove(0);
         }
     }
 
-    pub fn insert_char(&mut self, c: char) {
-        if let FieldBuffer::Text(ref mut text, ref mut cursor_pos) = &mut self.input {
-            text.insert(*cursor_pos as usize, c);
-            *cursor_pos += 1;
+    pub fn edit_input(&mut self, key: KeyEvent) -> EditResult {
+        match &mut self.input {
+            FieldBuffer::Text(editor) => editor.handle_key(key, &mut self.kill_buffer),
+            _ => EditResult::Ignored,
         }
     }
 
-    pub fn cursor_left(&mut self) {
-        if let FieldBuffer::Text(_, ref mut cursor_pos) = &mut self.input {
-            if *cursor_pos != 0 {
-                *cursor_pos -= 1;
+    /// Adds to the number being edited
+    pub fn step_number(&mut self, delta: f32) {
+        if let FieldBuffer::Text(editor) = &mut self.input {
+            if let Ok(stepped) = number::step(editor.text(), delta) {
+                editor.set_text(&stepped);
             }
         }
     }
 
-    pub fn cursor_right(&mut self) {
-        if let FieldBuffer::Text(ref mut text, ref mut cursor_pos) = &mut self.input {
-            if *cursor_pos != text.len() as u16 {
-                *cursor_pos += 1;
-            }
+    /// Why the number being edited can't be saved, if it can't
+    pub fn number_error(&self) -> Option<String> {
+        match &self.input {
+            FieldBuffer::Text(editor) => number::parse(editor.text()).err().map(|err| err.to_string()),
+            _ => None,
         }
     }
 
+    /// Vim mode of the editor in use, `None` when vim mode is off
+    pub fn editor_vim_mode(&self) -> Option<VimMode> {
+        match (&self.input, &self.menu_state) {
+            (FieldBuffer::Text(editor), InputMode::Input) => editor.vim_mode(),
+            (_, InputMode::AddItem(editor))
+            | (_, InputMode::Export(editor))
+            | (_, InputMode::Filter(editor))
+            | (_, InputMode::SaveView(editor)) => editor.vim_mode(),
+            _ => None,
+        }
+    }
+
+    /// Whether Esc should go to the editor instead of closing it
+    pub fn editor_wants_esc(&self) -> bool {
+        matches!(self.editor_vim_mode(), Some(VimMode::Insert) | Some(VimMode::Delete))
+    }
+
     pub fn save_field(&mut self) -> anyhow::Result<()> {
-        if let Some(_app_info) = &self.user_info {
+        // With items selected the edit only picks the value for all of them
+        if !self.selected_ids().is_empty() {
+            let value = self.input_value()?;
+            let field_id = self.info()?.fields[self.field_state].get_id().to_string();
+            self.start_bulk(BulkAction::SetField { field_id, value });
+            return Ok(());
+        }
+
+        if let Some(app_info) = &self.user_info {
             use ProjectV2ItemField::*;
 
+            let item_id = app_info.items[self.item_state].id.clone();
+            let field_id = app_info.fields[self.field_state].get_id().to_string();
+
             match self.get_field_at(self.item_state, self.field_state)? {
                 Empty(_v) => {}
                 TextValue { text: _, field: _ } => {
@@ -366,22 +1828,48 @@ impl App {
                     field: _,
                 } => {}
             };
+
+            let new = FieldValue::from_item_field(self.get_field_at(self.item_state, self.field_state)?);
+            let old = std::mem::replace(&mut self.edit_origin, FieldValue::Empty);
+
+            if old != new {
+                self.history.record(Change::Field {
+                    item_id,
+                    field_id,
+                    old,
+                    new,
+                });
+            }
         }
 
         Ok(())
     }
 
+    /// Value currently in the editor
+    pub fn input_value(&self) -> anyhow::Result<FieldValue> {
+        let field = &self.info()?.fields[self.field_state];
+
+        match &self.input {
+            FieldBuffer::Text(editor) => FieldValue::parse(field, editor.text()),
+            FieldBuffer::SingleSelect(options, index) => {
+                let option = &options[*index as usize];
+                Ok(FieldValue::SingleSelect {
+                    option_id: option.id.clone(),
+                    name: option.name.clone(),
+                })
+            }
+            FieldBuffer::Date(date, _) => Ok(FieldValue::Date(date.format(dates::FORMAT)?)),
+            _ => Err(anyhow!("Field can't be edited")),
+        }
+    }
+
     pub fn save_field_option(&mut self) -> anyhow::Result<()> {
         if let Some(app_info) = &self.user_info {
             if let FieldBuffer::SingleSelect(options, index) = &self.input {
                 let current_option = options[*index as usize].clone();
 
                 let _mutation = github::update_item_option(
-                    &self
-                        .id
-                        .clone()
-                        .ok_or_else(|| anyhow!("No Credential found"))?
-                        .token,
+                    &self.auth()?,
                     &app_info.projects[self.config.project_state].id,
                     &app_info.items[self.item_state].id,
                     app_info.fields[self.field_state].get_id(),
@@ -397,171 +1885,701 @@ impl App {
 
     pub fn save_field_number(&mut self) -> anyhow::Result<()> {
         if let Some(app_info) = &self.user_info {
-            if let FieldBuffer::Text(text, _cursor) = &self.input {
+            if let FieldBuffer::Text(editor) = &self.input {
+                let number = number::parse(editor.text())?;
                 let _mutation = github::update_item_number(
-                    &self
-                        .id
-                        .clone()
-                        .ok_or_else(|| anyhow!("No Credential found"))?
-                        .token,
+                    &self.auth()?,
+                    &app_info.projects[self.config.project_state].id,
+                    &app_info.items[self.item_state].id,
+                    app_info.fields[self.field_state].get_id(),
+                    number,
+                )?;
+
+                return self.set_field_at(self.item_state, self.field_state, &number.to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn save_field_date(&mut self) -> anyhow::Result<()> {
+        if let Some(app_info) = &self.user_info {
+            if let FieldBuffer::Date(date, _) = &self.input {
+                let format_date = date.format(dates::FORMAT)?;
+
+                let _mutation = github::update_item_date(
+                    &self.auth()?,
+                    &app_info.projects[self.config.project_state].id,
+                    &app_info.items[self.item_state].id,
+                    app_info.fields[self.field_state].get_id(),
+                    &format_date,
+                )?;
+
+                return self.set_field_at(self.item_state, self.field_state, &format_date);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn save_field_text(&mut self) -> anyhow::Result<()> {
+        if let Some(app_info) = &self.user_info {
+            if let FieldBuffer::Text(editor) = &self.input {
+                let text = editor.text().to_string();
+                let _mutation = github::update_item_text(
+                    &self.auth()?,
                     &app_info.projects[self.config.project_state].id,
                     &app_info.items[self.item_state].id,
                     app_info.fields[self.field_state].get_id(),
-                    text.parse()?,
+                    &text,
+                )?;
+
+                return self.set_field_at(self.item_state, self.field_state, &text);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn set_field_at(&mut self, item: usize, field: usize, s: &str) -> anyhow::Result<()> {
+        let info = self.info()?;
+        let index = info.fields[field].get_name().to_string();
+        let mut_info = self.mut_info()?;
+
+        mut_info.items[item].field_values.set_value(&index, s);
+
+        Ok(())
+    }
+
+    pub fn get_field_at(&self, item: usize, field: usize) -> anyhow::Result<&ProjectV2ItemField> {
+        if let Some(app_info) = &self.user_info {
+            let index_field = app_info.fields[field].get_name();
+            if app_info.items.len() == 0 {
+                anyhow::bail!("{:?}", self);
+            }
+            Ok(app_info.items[item]
+                .field_values
+                .get_from_field(&index_field))
+        } else {
+            anyhow::bail!("No app info")
+        }
+    }
+
+    pub fn shift_date(&mut self, d: i64) {
+        if let FieldBuffer::Date(ref mut date, _) = &mut self.input {
+            if let Some(new_date) = date.checked_add(Duration::days(d)) {
+                *date = new_date;
+            }
+        }
+    }
+
+    pub fn shift_month_forward(&mut self) {
+        if let FieldBuffer::Date(ref mut date, _) = &mut self.input {
+            if let Ok(new_date) = date.replace_month(date.month().next()) {
+                *date = new_date;
+            }
+        }
+    }
+
+    pub fn shift_month_back(&mut self) {
+        if let FieldBuffer::Date(ref mut date, _) = &mut self.input {
+            if let Ok(new_date) = date.replace_month(date.month().previous()) {
+                *date = new_date;
+            }
+        }
+    }
+
+    pub fn shift_year_forward(&mut self) {
+        if let FieldBuffer::Date(ref mut date, _) = &mut self.input {
+            if let Ok(new_date) = date.replace_year(date.year() + 1) {
+                *date = new_date;
+            }
+        }
+    }
+
+    pub fn shift_year_back(&mut self) {
+        if let FieldBuffer::Date(ref mut date, _) = &mut self.input {
+            if let Ok(new_date) = date.replace_year(date.year() - 1) {
+                *date = new_date;
+            }
+        }
+    }
+
+    pub fn date_today(&mut self) {
+        if let FieldBuffer::Date(ref mut date, _) = &mut self.input {
+            *date = dates::today(self.utc_offset);
+        }
+    }
+
+    /// Types into the date entry, moving the calendar whenever the text is a date
+    pub fn edit_date_entry(&mut self, key: KeyEvent) {
+        if let FieldBuffer::Date(ref mut date, ref mut entry) = &mut self.input {
+            entry.handle_key(key, &mut self.kill_buffer);
+
+            if let Ok(typed) = dates::parse(entry.text(), dates::today(self.utc_offset)) {
+                *date = typed;
+            }
+        }
+    }
+
+    /// Why the typed date can't be used, if any was typed
+    pub fn date_error(&self) -> Option<String> {
+        match &self.input {
+            FieldBuffer::Date(_, entry) if !entry.is_empty() => {
+                dates::parse(entry.text(), dates::today(self.utc_offset)).err().map(|err| err.to_string())
+            }
+            _ => None,
+        }
+    }
+
+    pub fn add_item(&mut self) -> Result<(), anyhow::Error> {
+        let auth = self.auth()?;
+        let group_value = self.group_value_for_new_item();
+
+        if let Some(app_info) = &mut self.user_info {
+            if let InputMode::AddItem(editor) = &self.menu_state {
+                let title = editor.text();
+                let mut item = github::add_draft_issue(
+                    &auth,
+                    &app_info.projects[self.config.project_state].id,
+                    "",
+                    title,
                 )?;
 
-                return self.set_field_at(self.item_state, self.field_state, &text.clone());
-            }
+                // Fill in the title locally until the next refresh
+                if let Some(title_field) = app_info.fields.iter().find(|f| f.get_type() == "TITLE") {
+                    item.field_values.nodes.push(title_field.default());
+                    item.field_values.set_value(title_field.get_name(), title);
+                }
+
+                let id = item.id.clone();
+                app_info.items.push(item);
+
+                // Added inside a group, so it takes the group's value
+                if let Some((field_id, value)) = group_value {
+                    self.set_item_field(&id, &field_id, &value)?;
+                }
+
+                if let Some(item) = self.info()?.items.iter().find(|i| i.id == id).cloned() {
+                    self.history.record(Change::AddItem(item));
+                }
+                self.apply_view();
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the loaded items to a file, in the format given by its extension
+    pub fn export(&self, path: &str) -> anyhow::Result<usize> {
+        let info = self.info()?;
+        let format = ExportFormat::from_path(path)?;
+        let fields = self.config.visible_fields(&self.project_id()?, &info.fields);
+
+        std::fs::write(path, export::export(&info.items, &fields, format)?)?;
+
+        Ok(info.items.len())
+    }
+
+    pub fn toggle_selected(&mut self) -> anyhow::Result<()> {
+        let id = self.info()?.items.get(self.item_state).map(|i| i.id.clone());
+
+        if let Some(id) = id {
+            match self.selected.iter().position(|s| s == &id) {
+                Some(index) => {
+                    self.selected.remove(index);
+                }
+                None => self.selected.push(id),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Starts visual mode, or adds the visual range to the selection if
+    /// already in it
+    pub fn toggle_visual(&mut self) {
+        if self.visual_anchor.is_some() {
+            self.selected = self.selected_ids();
+            self.visual_anchor = None;
+        } else {
+            self.visual_anchor = self
+                .info()
+                .ok()
+                .and_then(|info| info.items.get(self.item_state))
+                .map(|item| item.id.clone());
+        }
+    }
+
+    /// First and last index of the visual range. It shrinks to the cursor
+    /// when the anchor item is filtered out.
+    fn visual_range(&self) -> Option<(usize, usize)> {
+        let anchor = self.visual_anchor.as_ref()?;
+        let anchor = self
+            .info()
+            .ok()?
+            .items
+            .iter()
+            .position(|item| item.id == *anchor)
+            .unwrap_or(self.item_state);
+
+        Some((anchor.min(self.item_state), anchor.max(self.item_state)))
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selected.clear();
+        self.visual_anchor = None;
+    }
+
+    /// Selected items, including the visual range, in board order
+    pub fn selected_ids(&self) -> Vec<String> {
+        let Some(info) = &self.user_info else {
+            return Vec::new();
+        };
+
+        let range = self.visual_range();
+
+        info.items
+            .iter()
+            .enumerate()
+            .filter(|(i, item)| {
+                self.selected.contains(&item.id)
+                    || range.is_some_and(|(start, end)| (start..=end).contains(i))
+            })
+            .map(|(_, item)| item.id.clone())
+            .collect()
+    }
+
+    pub fn is_selected(&self, index: usize) -> bool {
+        let in_range = self
+            .visual_range()
+            .is_some_and(|(start, end)| (start..=end).contains(&index));
+
+        in_range
+            || self
+                .user_info
+                .as_ref()
+                .and_then(|info| info.items.get(index))
+                .is_some_and(|item| self.selected.contains(&item.id))
+    }
+
+    pub fn start_bulk(&mut self, action: BulkAction) {
+        self.bulk = Some(BulkJob::new(action, self.selected_ids()));
+        self.clear_selection();
+    }
+
+    /// Applies the running bulk action to its next item
+    pub fn bulk_step(&mut self) {
+        let Some(mut job) = self.bulk.take() else {
+            return;
+        };
+
+        match job.pending.pop() {
+            Some(item_id) => {
+                let title = self
+                    .info()
+                    .ok()
+                    .and_then(|info| info.items.iter().find(|i| i.id == item_id))
+                    .map_or(item_id.clone(), |i| i.title());
+
+                match self.bulk_apply(&job.action, &item_id) {
+                    Ok(change) => job.applied.push(change),
+                    Err(err) => job.failures.push(format!("{title}: {err}")),
+                }
+
+                self.bulk = Some(job);
+            }
+
+            None => {
+                self.menu_state = InputMode::Message(job.summary());
+
+                if !job.applied.is_empty() {
+                    self.history.record(Change::Batch(job.applied));
+                }
+            }
+        }
+    }
+
+    fn bulk_apply(&mut self, action: &BulkAction, item_id: &str) -> anyhow::Result<Change> {
+        let info = self.info()?;
+        let item = info
+            .items
+            .iter()
+            .find(|i| i.id == item_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Item is no longer in the project"))?;
+
+        let change = match action {
+            BulkAction::SetField { field_id, value } => {
+                let field = info
+                    .fields
+                    .iter()
+                    .find(|f| f.get_id() == field_id)
+                    .ok_or_else(|| anyhow!("Field is no longer in the project"))?;
+
+                Change::Field {
+                    item_id: item.id.clone(),
+                    field_id: field_id.clone(),
+                    old: FieldValue::from_item_field(item.field_values.get_from_field(field.get_name())),
+                    new: value.clone(),
+                }
+            }
+            BulkAction::Archive => Change::Archive(item),
+            BulkAction::Delete => Change::DeleteItem(item),
+        };
+
+        self.apply_change(change)
+    }
+
+    pub fn clear_field(&mut self) -> anyhow::Result<()> {
+        let field_id = self.info()?.fields[self.field_state].get_id().to_string();
+
+        if !self.selected_ids().is_empty() {
+            self.start_bulk(BulkAction::SetField {
+                field_id,
+                value: FieldValue::Empty,
+            });
+            return Ok(());
+        }
+
+        let Some(item) = self.info()?.items.get(self.item_state).cloned() else {
+            return Ok(());
+        };
+
+        let old = FieldValue::from_item_field(self.get_field_at(self.item_state, self.field_state)?);
+        if old == FieldValue::Empty {
+            return Ok(());
+        }
+
+        let change = self.apply_change(Change::Field {
+            item_id: item.id,
+            field_id,
+            old,
+            new: FieldValue::Empty,
+        })?;
+        self.history.record(change);
+
+        Ok(())
+    }
+
+    /// Question asked before running an action that needs confirming
+    pub fn confirm_message(&self, action: Action) -> String {
+        let count = self.selected_ids().len();
+        let verb = match action {
+            Action::ArchiveItem => "Archive",
+            _ => "Delete",
+        };
+
+        if count > 0 {
+            return format!("{verb} {count} selected items? (y/n)");
+        }
+
+        let title = self
+            .info()
+            .ok()
+            .and_then(|info| info.items.get(self.item_state))
+            .map_or_else(String::new, |item| item.title());
+        format!("{verb} \"{title}\"? Undo adds it back as a new item. (y/n)")
+    }
+
+    pub fn delete_item(&mut self) -> anyhow::Result<()> {
+        if !self.selected_ids().is_empty() {
+            self.start_bulk(BulkAction::Delete);
+            return Ok(());
+        }
+
+        let item = self.info()?.items.get(self.item_state).cloned();
+
+        if let Some(item) = item {
+            let change = self.apply_change(Change::DeleteItem(item))?;
+            self.history.record(change);
+        }
+
+        Ok(())
+    }
+
+    pub fn archive_item(&mut self) -> anyhow::Result<()> {
+        if !self.selected_ids().is_empty() {
+            self.start_bulk(BulkAction::Archive);
+            return Ok(());
+        }
+
+        let item = self.info()?.items.get(self.item_state).cloned();
+
+        if let Some(item) = item {
+            let change = self.apply_change(Change::Archive(item))?;
+            self.history.record(change);
         }
 
         Ok(())
     }
 
-    pub fn save_field_date(&mut self) -> anyhow::Result<()> {
-        if let Some(app_info) = &self.user_info {
-            if let FieldBuffer::Date(date) = self.input {
-                let format = format_description::parse("[year]-[month]-[day]")?;
-                let format_date = date.format(&format)?;
-
-                let _mutation = github::update_item_date(
-                    &self
-                        .id
-                        .clone()
-                        .ok_or_else(|| anyhow!("No Credential found"))?
-                        .token,
-                    &app_info.projects[self.config.project_state].id,
-                    &app_info.items[self.item_state].id,
-                    app_info.fields[self.field_state].get_id(),
-                    &format_date,
-                )?;
-
-                return self.set_field_at(self.item_state, self.field_state, &format_date);
+    pub fn undo(&mut self) -> anyhow::Result<()> {
+        if let Some(change) = self.history.pop_undo() {
+            match self.apply_change(change.clone().inverse()) {
+                Ok(done) => self.history.push_redo(done.inverse()),
+                // What was undone can be redone, the rest is still to undo
+                Err(err) => match err.downcast::<PartlyApplied>() {
+                    Ok(partly) => {
+                        let message = partly.to_string();
+                        self.history.push_redo(partly.applied.inverse());
+                        self.history.push_undo(partly.remaining.inverse());
+                        return Err(anyhow!(message));
+                    }
+                    Err(err) => {
+                        self.history.push_undo(change);
+                        return Err(err);
+                    }
+                },
             }
         }
 
         Ok(())
     }
 
-    pub fn save_field_text(&mut self) -> anyhow::Result<()> {
-        if let Some(app_info) = &self.user_info {
-            if let FieldBuffer::Text(text, _cursor_pos) = &self.input {
-                let _mutation = github::update_item_text(
-                    &self
-                        .id
-                        .clone()
-                        .ok_or_else(|| anyhow!("No Credential found"))?
-                        .token,
-                    &app_info.projects[self.config.project_state].id,
-                    &app_info.items[self.item_state].id,
-                    app_info.fields[self.field_state].get_id(),
-                    &text,
-                )?;
-
-                return self.set_field_at(self.item_state, self.field_state, &text.clone());
+    pub fn redo(&mut self) -> anyhow::Result<()> {
+        if let Some(change) = self.history.pop_redo() {
+            match self.apply_change(change.clone()) {
+                Ok(done) => self.history.push_undo(done),
+                Err(err) => match err.downcast::<PartlyApplied>() {
+                    Ok(partly) => {
+                        let message = partly.to_string();
+                        self.history.push_undo(partly.applied);
+                        self.history.push_redo(partly.remaining);
+                        return Err(anyhow!(message));
+                    }
+                    Err(err) => {
+                        self.history.push_redo(change);
+                        return Err(err);
+                    }
+                },
             }
         }
 
         Ok(())
     }
 
-    pub fn set_field_at(&mut self, item: usize, field: usize, s: &str) -> anyhow::Result<()> {
-        let info = self.info()?;
-        let index = info.fields[field].get_name().to_string();
-        let mut_info = self.mut_info()?;
+    /// Sends a change to GitHub and mirrors it locally. Returns the change as
+    /// it was applied, as re-adding an item gives it a new ID.
+    pub fn apply_change(&mut self, change: Change) -> anyhow::Result<Change> {
+        let auth = self.auth()?;
+        let project_id = self.project_id()?;
+
+        let applied = match change {
+            Change::Field {
+                item_id,
+                field_id,
+                old,
+                new,
+            } => {
+                self.set_item_field(&item_id, &field_id, &new)?;
+                Change::Field {
+                    item_id,
+                    field_id,
+                    old,
+                    new,
+                }
+            }
 
-        mut_info.items[item].field_values.set_value(&index, s);
+            Change::AddItem(item) => {
+                let mut new_item = match (item.content_id(), &item.content) {
+                    (Some(content_id), _) => github::add_item_by_id(&auth, &project_id, content_id)?,
+                    (None, Some(Content::DraftIssue { body, .. })) => {
+                        github::add_draft_issue(&auth, &project_id, body, &item.title())?
+                    }
+                    (None, _) => github::add_draft_issue(&auth, &project_id, "", &item.title())?,
+                };
 
-        Ok(())
-    }
+                new_item.field_values = item.field_values.clone();
+                new_item.content = item.content.clone();
+                self.history.replace_item_id(&item.id, &new_item.id);
+                self.mut_info()?.items.push(new_item.clone());
 
-    pub fn get_field_at(&self, item: usize, field: usize) -> anyhow::Result<&ProjectV2ItemField> {
-        if let Some(app_info) = &self.user_info {
-            let index_field = app_info.fields[field].get_name();
-            if app_info.items.len() == 0 {
-                anyhow::bail!("{:?}", self);
+                // Restore the values the item had before it was removed
+                let mut restores = item
+                    .field_values
+                    .nodes
+                    .iter()
+                    .filter(|value| value.get_type() != "TITLE")
+                    .map(|value| Change::Field {
+                        item_id: new_item.id.clone(),
+                        field_id: value.get_field_id().to_string(),
+                        old: FieldValue::Empty,
+                        new: FieldValue::from_item_field(value),
+                    })
+                    .filter(|change| !matches!(change, Change::Field { new: FieldValue::Empty, .. }));
+
+                while let Some(restore) = restores.next() {
+                    let Change::Field { field_id, new, .. } = &restore else {
+                        continue;
+                    };
+                    if let Err(error) = self.set_item_field(&new_item.id, field_id, new) {
+                        // The item is there either way, the values that weren't
+                        // restored are left to be made again
+                        let remaining: Vec<Change> = std::iter::once(restore).chain(restores).collect();
+                        let added = self.clear_local_values(&new_item.id, &remaining)?;
+                        return Err(PartlyApplied {
+                            applied: Change::AddItem(added),
+                            remaining: Change::Batch(remaining),
+                            error,
+                        }
+                        .into());
+                    }
+                }
+
+                Change::AddItem(new_item)
             }
-            Ok(app_info.items[item]
-                .field_values
-                .get_from_field(&index_field))
-        } else {
-            anyhow::bail!("No app info")
-        }
-    }
 
-    pub fn shift_date(&mut self, d: i64) {
-        if let FieldBuffer::Date(ref mut date) = &mut self.input {
-            if let Some(new_date) = date.checked_add(Duration::days(d)) {
-                *date = new_date;
+            Change::DeleteItem(item) => {
+                github::delete_item(&auth, &project_id, &item.id)?;
+                self.remove_local_item(&item.id)?;
+                Change::DeleteItem(item)
             }
-        }
-    }
 
-    pub fn shift_month_forward(&mut self) {
-        if let FieldBuffer::Date(ref mut date) = &mut self.input {
-            if let Ok(new_date) = date.replace_month(date.month().next()) {
-                *date = new_date;
+            Change::Archive(item) => {
+                github::set_item_archived(&auth, &project_id, &item.id, true)?;
+                self.remove_local_item(&item.id)?;
+                Change::Archive(item)
             }
-        }
-    }
 
-    pub fn shift_month_back(&mut self) {
-        if let FieldBuffer::Date(ref mut date) = &mut self.input {
-            if let Ok(new_date) = date.replace_month(date.month().previous()) {
-                *date = new_date;
+            Change::Unarchive(item) => {
+                github::set_item_archived(&auth, &project_id, &item.id, false)?;
+                self.mut_info()?.items.push(item.clone());
+                self.apply_view();
+                Change::Unarchive(item)
             }
-        }
+
+            // Stops at the first failure, reporting which changes were made
+            Change::Batch(changes) => {
+                let mut applied = Vec::new();
+                let mut changes = changes.into_iter();
+
+                while let Some(change) = changes.next() {
+                    let (remaining, error) = match self.apply_change(change.clone()) {
+                        Ok(done) => {
+                            applied.push(done);
+                            continue;
+                        }
+                        // An item re-added without all of its values
+                        Err(error) => match error.downcast::<PartlyApplied>() {
+                            Ok(partly) => {
+                                applied.push(partly.applied);
+                                (partly.remaining, partly.error)
+                            }
+                            Err(error) if applied.is_empty() => return Err(error),
+                            Err(error) => (change, error),
+                        },
+                    };
+
+                    return Err(PartlyApplied {
+                        applied: Change::Batch(applied),
+                        remaining: Change::Batch(std::iter::once(remaining).chain(changes).collect()),
+                        error,
+                    }
+                    .into());
+                }
+                Change::Batch(applied)
+            }
+        };
+
+        Ok(applied)
     }
 
-    pub fn shift_year_forward(&mut self) {
-        if let FieldBuffer::Date(ref mut date) = &mut self.input {
-            if let Ok(new_date) = date.replace_year(date.year() + 1) {
-                *date = new_date;
+    /// Writes a single field value to GitHub and to the loaded items
+    pub fn set_item_field(
+        &mut self,
+        item_id: &str,
+        field_id: &str,
+        value: &FieldValue,
+    ) -> anyhow::Result<()> {
+        let _mutation = github::update_item_field(
+            &self.auth()?,
+            &self.project_id()?,
+            item_id,
+            field_id,
+            value,
+        )?;
+
+        let info = self.mut_info()?;
+        let field = info.fields.iter().find(|f| f.get_id() == field_id).cloned();
+
+        if let (Some(field), Some(item)) = (field, info.items.iter_mut().find(|i| i.id == item_id)) {
+            match value {
+                FieldValue::Empty => item.field_values.remove_value(field.get_name()),
+                _ => {
+                    if let ProjectV2ItemField::Empty(_) = item.field_values.get_from_field(field.get_name()) {
+                        item.field_values.nodes.push(field.default());
+                    }
+                    item.field_values.set_value(field.get_name(), &value.display());
+                }
             }
         }
+
+        Ok(())
     }
 
-    pub fn shift_year_back(&mut self) {
-        if let FieldBuffer::Date(ref mut date) = &mut self.input {
-            if let Ok(new_date) = date.replace_year(date.year() - 1) {
-                *date = new_date;
-            }
+    /// Empties the loaded values that the given field changes would have set,
+    /// returning the item as it now is
+    fn clear_local_values(&mut self, item_id: &str, changes: &[Change]) -> anyhow::Result<Item> {
+        let info = self.mut_info()?;
+        let names: Vec<String> = changes
+            .iter()
+            .filter_map(|change| match change {
+                Change::Field { field_id, .. } => info.fields.iter().find(|f| f.get_id() == field_id),
+                _ => None,
+            })
+            .map(|field| field.get_name().to_string())
+            .collect();
+
+        let item = info
+            .items
+            .iter_mut()
+            .find(|i| i.id == item_id)
+            .ok_or_else(|| anyhow!("Item not loaded"))?;
+        for name in names {
+            item.field_values.remove_value(&name);
         }
+
+        Ok(item.clone())
     }
 
-    pub fn add_item(&mut self) -> Result<(), anyhow::Error> {
-        if let Some(app_info) = &mut self.user_info {
-            if let InputMode::AddItem(item, _) = &self.menu_state {
-                app_info.items.push(github::add_draft_issue(
-                    &self.id.as_ref().unwrap().token,
-                    &app_info.projects[self.config.project_state].id,
-                    "",
-                    &item,
-                )?);
-            }
+    fn remove_local_item(&mut self, item_id: &str) -> anyhow::Result<()> {
+        let info = self.mut_info()?;
+        info.items.retain(|i| i.id != item_id);
+        let len = info.items.len();
+
+        if self.item_state >= len {
+            self.item_state = len.saturating_sub(1);
         }
+
         Ok(())
     }
 }
 
 pub fn insert_mode_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<()> {
-    match key.code {
-        KeyCode::Esc => {
+    if let Some(_app_info) = &app.user_info {
+        use ProjectV2ItemField::*;
+
+        let mode = match app.get_field_at(app.item_state, app.field_state)? {
+            SingleSelectValue { name: _, field: _ } => Mode::List,
+            DateValue { date: _, field: _ } => Mode::Calendar,
+            _ => Mode::Prompt,
+        };
+        let action = app.key_action(mode, key);
+
+        // Esc goes to the editor while it is in vim's insert mode
+        if action == Some(Action::Cancel) && !(key.code == KeyCode::Esc && app.editor_wants_esc()) {
             app.menu_state = InputMode::Normal;
             app.input = FieldBuffer::None;
+            return Ok(());
         }
-        _ => {}
-    }
-
-    if let Some(_app_info) = &app.user_info {
-        use ProjectV2ItemField::*;
 
         match app.get_field_at(app.item_state, app.field_state)? {
             // Single select editing
-            SingleSelectValue { name: _, field: _ } => match key.code {
-                KeyCode::Char('j') | KeyCode::Down => app.shift_option_down(),
-                KeyCode::Char('k') | KeyCode::Up => app.shift_option_up(),
+            SingleSelectValue { name: _, field: _ } => match action {
+                Some(Action::Down) => app.shift_option_down(),
+                Some(Action::Up) => app.shift_option_up(),
 
-                KeyCode::Enter => {
+                Some(Action::Confirm) => {
                     app.save_field()?;
                     app.menu_state = InputMode::Normal;
                     app.input = FieldBuffer::None;
@@ -571,65 +2589,88 @@ pub fn insert_mode_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<()> {
             },
 
             // Text editing
-            TextValue { text: _, field: _ } => match key.code {
-                KeyCode::Char(a) => app.insert_char(a),
-                KeyCode::Backspace => app.backspace(),
+            TextValue { text: _, field } => match action {
+                Some(Action::Confirm) => {
+                    if let FieldBuffer::Text(editor) = &app.input {
+                        let (field_id, value) = (field.get_id().to_string(), editor.text().to_string());
+                        app.remember_input(&field_id, &value);
+                    }
 
-                KeyCode::Enter => {
                     app.save_field()?;
                     app.menu_state = InputMode::Normal;
                     app.input = FieldBuffer::None;
                 }
 
-                KeyCode::Left => app.cursor_left(),
-                KeyCode::Right => app.cursor_right(),
-
-                _ => {}
+                _ => {
+                    app.edit_input(key);
+                }
             },
 
-            // Date editing, uses calendar widget
-            DateValue { date: _, field: _ } => match key.code {
-                KeyCode::Left | KeyCode::Char('h') => app.shift_date(1),
-                KeyCode::Right | KeyCode::Char('l') => app.shift_date(-1),
-                KeyCode::Up | KeyCode::Char('k') => app.shift_date(-7),
-                KeyCode::Down | KeyCode::Char('j') => app.shift_date(7),
+            // Date editing, uses calendar widget. Once something is typed
+            // every key goes to the date entry instead of moving the calendar.
+            DateValue { date: _, field: _ } => {
+                let typing = matches!(&app.input, FieldBuffer::Date(_, entry) if !entry.is_empty());
 
-                KeyCode::Char('J') => app.shift_month_forward(),
-                KeyCode::Char('K') => app.shift_month_back(),
+                match action {
+                    Some(Action::Confirm) if app.date_error().is_none() => {
+                        app.save_field()?;
+                        app.menu_state = InputMode::Normal;
+                    }
+                    Some(Action::Confirm) => {}
 
-                KeyCode::Char('L') => app.shift_year_forward(),
-                KeyCode::Char('H') => app.shift_year_back(),
+                    _ if typing => app.edit_date_entry(key),
 
-                KeyCode::Enter => {
-                    app.save_field()?;
-                    app.menu_state = InputMode::Normal;
-                }
+                    Some(Action::Left) => app.shift_date(-1),
+                    Some(Action::Right) => app.shift_date(1),
+                    Some(Action::Up) => app.shift_date(-7),
+                    Some(Action::Down) => app.shift_date(7),
 
-                _ => {}
-            },
+                    Some(Action::NextMonth) => app.shift_month_forward(),
+                    Some(Action::PreviousMonth) => app.shift_month_back(),
+
+                    Some(Action::NextYear) => app.shift_year_forward(),
+                    Some(Action::PreviousYear) => app.shift_year_back(),
+
+                    Some(Action::Today) => app.date_today(),
+
+                    _ => app.edit_date_entry(key),
+                }
+            }
 
             NumberValue {
                 number: _,
                 field: _,
-            } => match key.code {
-                KeyCode::Char(a) => {
-                    if a.is_numeric() {
-                        app.insert_char(a);
+            } => {
+                let typing = app.editor_vim_mode().is_none_or(|mode| mode == VimMode::Insert);
+                let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+
+                match (action, key.code) {
+                    // Invalid numbers stay open, with the error shown under them
+                    (Some(Action::Confirm), _) if app.number_error().is_none() => {
+                        app.save_field()?;
+                        app.menu_state = InputMode::Normal;
+                        app.input = FieldBuffer::None;
+                    }
+                    (Some(Action::Confirm), _) => {}
+
+                    (_, KeyCode::Up) => app.step_number(1.0),
+                    (_, KeyCode::Down) => app.step_number(-1.0),
+                    (_, KeyCode::PageUp) => app.step_number(10.0),
+                    (_, KeyCode::PageDown) => app.step_number(-10.0),
+                    (_, KeyCode::Char('a')) if ctrl && !typing => app.step_number(1.0),
+                    (_, KeyCode::Char('x')) if ctrl && !typing => app.step_number(-1.0),
+
+                    // Keys that can't be part of a number aren't typed
+                    (_, KeyCode::Char(a))
+                        if typing
+                            && !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+                            && !number::is_number_char(a) => {}
+
+                    _ => {
+                        app.edit_input(key);
                     }
                 }
-                KeyCode::Backspace => app.backspace(),
-
-                KeyCode::Enter => {
-                    app.save_field()?;
-                    app.menu_state = InputMode::Normal;
-                    app.input = FieldBuffer::None;
-                }
-
-                KeyCode::Left => app.cursor_left(),
-                KeyCode::Right => app.cursor_right(),
-
-                _ => {}
-            },
+            }
 
             IterationValue {
                 duration: _,
@@ -645,64 +2686,316 @@ pub fn insert_mode_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<()> {
 }
 
 pub fn normal_mode_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<()> {
-    Ok(match key.code {
-        KeyCode::Char('q') => app.exit = true,
-        KeyCode::Char('a') => app.menu_state = InputMode::AddItem(String::from(""), 0),
+    if let Some(action) = app.key_action(Mode::Normal, key) {
+        if let Err(err) = app.perform(action) {
+            app.menu_state = InputMode::Error(err.to_string());
+        }
+    }
+
+    Ok(())
+}
+
+/// Typing searches the actions, Enter runs the highlighted one
+pub fn palette_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<()> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let action = app.key_action(Mode::Prompt, key);
+
+    if let InputMode::Palette(ref mut query, ref mut index) = app.menu_state {
+        let matches = action::search(query.text());
+
+        match (action, key.code) {
+            (Some(Action::Cancel), _) => app.menu_state = InputMode::Normal,
+
+            (_, KeyCode::Down | KeyCode::Tab) => *index = (*index + 1).min(matches.len().saturating_sub(1)),
+            (_, KeyCode::Char('n')) if ctrl => *index = (*index + 1).min(matches.len().saturating_sub(1)),
+            (_, KeyCode::Up | KeyCode::BackTab) => *index = index.saturating_sub(1),
+            (_, KeyCode::Char('p')) if ctrl => *index = index.saturating_sub(1),
 
-        KeyCode::Char('j') | KeyCode::Down => app.next(),
-        KeyCode::Char('k') | KeyCode::Up => app.previous(),
-        KeyCode::Char('h') | KeyCode::Left => app.left(),
-        KeyCode::Char('l') | KeyCode::Right => app.right(),
+            (Some(Action::Confirm), _) => {
+                if let Some(&action) = matches.get(*index) {
+                    app.menu_state = InputMode::Normal;
+                    if let Err(err) = app.perform(action) {
+                        app.menu_state = InputMode::Error(err.to_string());
+                    }
+                }
+            }
 
-        KeyCode::Char('i') => match app.begin_editing() {
-            Err(err) => {
-                app.menu_state = InputMode::Error(err.to_string());
+            _ => {
+                query.handle_key(key, &mut app.kill_buffer);
+                *index = 0;
             }
-            _ => {}
-        },
+        }
+    }
+    Ok(())
+}
 
-        KeyCode::Char('p') => app.menu_state = InputMode::SwitchProject(app.config.project_state),
+pub fn filter_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<()> {
+    let wants_esc = key.code == KeyCode::Esc && app.editor_wants_esc();
+    let action = app.key_action(Mode::Prompt, key);
 
-        _ => {}
-    })
+    if let InputMode::Filter(ref mut query) = app.menu_state {
+        match action {
+            Some(Action::Confirm) => {
+                let query = query.text().to_string();
+                app.remember_input("filter", &query);
+                app.menu_state = InputMode::Normal;
+
+                if let Err(err) = app.set_filter(&query) {
+                    app.menu_state = InputMode::Error(err.to_string());
+                }
+            }
+
+            Some(Action::Cancel) if !wants_esc => app.menu_state = InputMode::Normal,
+
+            _ => {
+                query.handle_key(key, &mut app.kill_buffer);
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn save_view_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<()> {
+    let wants_esc = key.code == KeyCode::Esc && app.editor_wants_esc();
+    let action = app.key_action(Mode::Prompt, key);
+
+    if let InputMode::SaveView(ref mut name) = app.menu_state {
+        match action {
+            Some(Action::Confirm) if !name.text().trim().is_empty() => {
+                let name = name.text().trim().to_string();
+                app.remember_input("view", &name);
+                app.menu_state = InputMode::Normal;
+                app.save_view(&name)?;
+            }
+
+            Some(Action::Cancel) if !wants_esc => app.menu_state = InputMode::Normal,
+
+            _ => {
+                name.handle_key(key, &mut app.kill_buffer);
+            }
+        }
+    }
+    Ok(())
 }
 
 pub fn add_item_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<()> {
-    if let InputMode::AddItem(ref mut item, ref mut cursor) = app.menu_state {
-        match key.code {
-            KeyCode::Char(a) => {
-                item.insert(*cursor, a);
-                *cursor += 1;
-            }
-            KeyCode::Backspace if *cursor != 0 => {
-                item.remove(*cursor - 1);
-                *cursor -= 1;
-            },
+    let wants_esc = key.code == KeyCode::Esc && app.editor_wants_esc();
+    let action = app.key_action(Mode::Prompt, key);
 
-            KeyCode::Enter if item.len() != 0 => {
+    if let InputMode::AddItem(ref mut item) = app.menu_state {
+        match action {
+            Some(Action::Confirm) if !item.is_empty() => {
+                let title = item.text().to_string();
                 app.add_item()?;
+                app.remember_input("add item", &title);
                 app.menu_state = InputMode::Normal;
             }
 
-            KeyCode::Esc => app.menu_state = InputMode::Normal,
+            Some(Action::Cancel) if !wants_esc => app.menu_state = InputMode::Normal,
+
+            _ => {
+                item.handle_key(key, &mut app.kill_buffer);
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn export_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<()> {
+    let wants_esc = key.code == KeyCode::Esc && app.editor_wants_esc();
+    let action = app.key_action(Mode::Prompt, key);
+
+    if let InputMode::Export(ref mut path) = app.menu_state {
+        match action {
+            Some(Action::Confirm) if !path.is_empty() => {
+                let path = path.text().to_string();
+                app.remember_input("export", &path);
+                app.menu_state = match app.export(&path) {
+                    Ok(count) => InputMode::Message(format!("Exported {count} items to {path}")),
+                    Err(err) => InputMode::Error(err.to_string()),
+                };
+            }
+
+            Some(Action::Cancel) if !wants_esc => app.menu_state = InputMode::Normal,
+
+            _ => {
+                path.handle_key(key, &mut app.kill_buffer);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Only lets a running bulk action be stopped, leaving the rest undone
+pub fn bulk_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<()> {
+    if let (KeyCode::Esc, Some(job)) = (key.code, &mut app.bulk) {
+        job.pending.clear();
+    }
+    Ok(())
+}
+
+/// Clicks select cells and pick from popups, double clicks begin editing and
+/// the wheel scrolls
+pub fn mouse_events(mouse: MouseEvent, app: &mut App) -> anyhow::Result<()> {
+    let (x, y) = (mouse.column, mouse.row);
+
+    match mouse.kind {
+        MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {
+            let down = mouse.kind == MouseEventKind::ScrollDown;
+
+            match (&app.menu_state, &app.input) {
+                (InputMode::Normal, _) => app.jump(if down { 3 } else { -3 }),
+                (InputMode::Input, FieldBuffer::SingleSelect(_, _)) if down => app.shift_option_down(),
+                (InputMode::Input, FieldBuffer::SingleSelect(_, _)) => app.shift_option_up(),
+                (InputMode::Input, FieldBuffer::Date(_, _)) => app.shift_date(if down { 7 } else { -7 }),
+                _ => {}
+            }
+        }
+
+        MouseEventKind::Down(MouseButton::Left) => {
+            let double = app.register_click(x, y);
+
+            match app.menu_state {
+                InputMode::Input => {
+                    if app.click_popup(x, y)? {
+                        return Ok(());
+                    }
+
+                    // Clicking anywhere else cancels the edit, like Esc
+                    app.menu_state = InputMode::Normal;
+                    app.input = FieldBuffer::None;
+                    app.click_cell(x, y);
+                }
+
+                InputMode::Normal => {
+                    let clicked = app.click_cell(x, y);
+                    if clicked && double {
+                        if let Err(err) = app.begin_editing() {
+                            app.menu_state = InputMode::Error(err.to_string());
+                        }
+                    }
+                }
+
+                InputMode::Error(_) | InputMode::Message(_) | InputMode::Confirm(_) => {
+                    app.menu_state = InputMode::Normal
+                }
+
+                _ => {}
+            }
+        }
+
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Switches between burn-down and burn-up, any way of leaving a list closes
+/// the insights
+pub fn insights_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<()> {
+    let action = app.key_action(Mode::List, key);
 
-            KeyCode::Left if *cursor != 0 => *cursor -= 1,
-            KeyCode::Right if *cursor != item.len() => *cursor += 1,
+    if let InputMode::Insights(ref mut burnup) = app.menu_state {
+        match (action, key.code) {
+            (_, KeyCode::Char('b')) => *burnup = !*burnup,
+            (Some(Action::Cancel | Action::Confirm), _) | (_, KeyCode::Char('q' | 'I')) => {
+                app.menu_state = InputMode::Normal
+            }
             _ => {}
         }
     }
     Ok(())
 }
 
+/// Runs the action being confirmed on `y` or Enter, anything else backs out
+pub fn confirm_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<()> {
+    if let InputMode::Confirm(action) = app.menu_state {
+        app.menu_state = InputMode::Normal;
+
+        if matches!(key.code, KeyCode::Char('y' | 'Y') | KeyCode::Enter) {
+            match action {
+                Action::ArchiveItem => app.archive_item()?,
+                _ => app.delete_item()?,
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Closes error and message windows
+pub fn message_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<()> {
+    if let KeyCode::Esc | KeyCode::Enter = key.code {
+        app.menu_state = InputMode::Normal;
+    }
+    Ok(())
+}
+
 pub fn switch_project_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<()> {
-    Ok(match key.code {
-        KeyCode::Esc => app.menu_state = InputMode::Normal,
+    match app.key_action(Mode::List, key) {
+        Some(Action::Cancel) => app.menu_state = InputMode::Normal,
 
-        KeyCode::Char('j') | KeyCode::Down => app.shift_project_down(),
-        KeyCode::Char('k') | KeyCode::Up => app.shift_project_up(),
+        Some(Action::Down) => app.shift_project_down(),
+        Some(Action::Up) => app.shift_project_up(),
 
-        KeyCode::Enter => app.select_project(),
+        Some(Action::Confirm) => app.select_project(),
 
         _ => {}
-    })
+    }
+    Ok(())
+}
+
+/// `m` merges the projects of every account into the project list
+pub fn switch_account_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<()> {
+    match (app.key_action(Mode::List, key), key.code) {
+        (Some(Action::Cancel), _) => app.menu_state = InputMode::Normal,
+
+        (Some(Action::Down), _) => app.shift_account_down(),
+        (Some(Action::Up), _) => app.shift_account_up(),
+
+        (Some(Action::Confirm), _) => app.select_account(),
+        (_, KeyCode::Char('m')) => app.toggle_merge_accounts(),
+
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Scrolls the help screen, any way of leaving a list closes it
+pub fn help_keys(key: KeyEvent, app: &mut App) -> anyhow::Result<()> {
+    let action = app.key_action(Mode::List, key);
+
+    if let InputMode::Help(ref mut scroll) = app.menu_state {
+        match (action, key.code) {
+            (Some(Action::Down), _) => *scroll += 1,
+            (Some(Action::Up), _) => *scroll = scroll.saturating_sub(1),
+            (Some(Action::Cancel | Action::Confirm), _) | (_, KeyCode::Char('q' | '?')) => {
+                app.menu_state = InputMode::Normal
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(precision: Option<usize>, prefix: &str, suffix: &str) -> NumberFormat {
+        NumberFormat {
+            field: String::from("Estimate"),
+            precision,
+            prefix: prefix.to_string(),
+            suffix: suffix.to_string(),
+        }
+    }
+
+    #[test]
+    fn number_formats() {
+        assert_eq!(format(None, "", "").apply(2.5), "2.5");
+        assert_eq!(format(Some(2), "$", "").apply(3.0), "$3.00");
+        assert_eq!(format(Some(0), "", " pts").apply(2.6), "3 pts");
+        assert_eq!(format(Some(1), "", "").apply(-0.25), "-0.2");
+        assert_eq!(format(None, "~", "h").apply(1e3), "~1000h");
+    }
 }
@@ -0,0 +1,63 @@
+use crate::history::{Change, FieldValue};
+
+/// An action applied to every selected item
+#[derive(Debug, Clone)]
+pub enum BulkAction {
+    SetField { field_id: String, value: FieldValue },
+    Archive,
+    Delete,
+}
+
+impl BulkAction {
+    pub fn verb(&self) -> &str {
+        match self {
+            BulkAction::SetField {
+                field_id: _,
+                value: FieldValue::Empty,
+            } => "Cleared",
+            BulkAction::SetField { .. } => "Updated",
+            BulkAction::Archive => "Archived",
+            BulkAction::Delete => "Deleted",
+        }
+    }
+}
+
+/// A bulk action in progress, one item is handled per frame so progress can
+/// be drawn in between.
+#[derive(Debug)]
+pub struct BulkJob {
+    pub action: BulkAction,
+    pub pending: Vec<String>,
+    pub total: usize,
+    pub applied: Vec<Change>,
+    pub failures: Vec<String>,
+}
+
+impl BulkJob {
+    pub fn new(action: BulkAction, item_ids: Vec<String>) -> BulkJob {
+        BulkJob {
+            action,
+            total: item_ids.len(),
+            pending: item_ids.into_iter().rev().collect(),
+            applied: Vec::new(),
+            failures: Vec::new(),
+        }
+    }
+
+    pub fn done(&self) -> usize {
+        self.total - self.pending.len()
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!("{} {} of {} items", self.action.verb(), self.applied.len(), self.total);
+
+        if !self.failures.is_empty() {
+            summary.push_str("\n\nFailed:");
+            for failure in &self.failures {
+                summary.push_str(&format!("\n{failure}"));
+            }
+        }
+
+        summary
+    }
+}
@@ -0,0 +1,521 @@
+use crate::app::{self, Account, Config};
+use crate::credentials::{self, Saved};
+use crate::export::{self, ExportFormat};
+use crate::filter::Filter;
+use crate::github::{self, Auth};
+use crate::history::FieldValue;
+use crate::import;
+use crate::project::*;
+use crate::text;
+use anyhow::anyhow;
+use serde_json::{json, Value};
+use std::fmt;
+
+const USAGE: &str = "Usage: gh_projects_tui [COMMAND] [OPTIONS]
+
+Runs the interactive editor when no command is given. The GitHub token is
+taken from --token-file, GH_TOKEN, GITHUB_TOKEN or the gh CLI, in that order,
+before the login saved by the editor.
+
+Commands:
+    projects list                       List your projects
+    items list [--filter QUERY]         List the items of a project
+    item set <ITEM> <FIELD> <VALUE>     Set a field of an item, by item ID or title
+    item add <TITLE>                    Add a draft issue
+    fields list                         List the fields of a project
+    export [--format FORMAT] [-o FILE]  Export items as csv, json or md
+    import <FILE> [--dry-run]           Add items from a csv or json file
+    logout                              Delete the saved login of the account
+
+Options:
+    --project NAME    Project to use as Owner/Name or just its name, defaults to
+                      the one last opened. Also works without a command
+    --account NAME    Account from the config to use, defaults to the current one
+    --filter QUERY    Filter items, e.g. 'status:Todo,Done -priority:Low'
+    --json            Print JSON instead of a table
+    --format FORMAT   Export format, guessed from the output file if not given
+    -o, --output FILE Write the export to a file instead of printing it
+    --dry-run         Only print what an import would change
+    --token-file FILE Read the GitHub token from a file, also works without a command
+    -h, --help        Print this message";
+
+/// Options that take a value
+const VALUE_FLAGS: [&str; 6] = ["project", "account", "filter", "format", "output", "token-file"];
+
+/// Error in the command line itself, reported with usage and exit code 2
+#[derive(Debug)]
+struct UsageError(String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for UsageError {}
+
+fn usage_error(msg: &str) -> anyhow::Error {
+    UsageError(msg.to_string()).into()
+}
+
+#[derive(Debug, Default)]
+pub struct Args {
+    pub positional: Vec<String>,
+    pub options: Vec<(String, String)>,
+    pub json: bool,
+    pub dry_run: bool,
+    pub help: bool,
+}
+
+impl Args {
+    pub fn parse(args: &[String]) -> anyhow::Result<Args> {
+        let mut parsed = Args::default();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--json" => parsed.json = true,
+                "--dry-run" => parsed.dry_run = true,
+                "-h" | "--help" => parsed.help = true,
+                "-o" => parsed.options.push((
+                    String::from("output"),
+                    args.next()
+                        .ok_or_else(|| usage_error("-o needs a value"))?
+                        .clone(),
+                )),
+                _ if arg.starts_with("--") => {
+                    let (name, value) = match arg[2..].split_once('=') {
+                        Some((name, value)) => (name.to_string(), value.to_string()),
+                        None => (
+                            arg[2..].to_string(),
+                            args.next()
+                                .ok_or_else(|| usage_error(&format!("{arg} needs a value")))?
+                                .clone(),
+                        ),
+                    };
+
+                    if !VALUE_FLAGS.contains(&name.as_str()) {
+                        return Err(usage_error(&format!("Unknown option --{name}")));
+                    }
+
+                    parsed.options.push((name, value));
+                }
+                _ => parsed.positional.push(arg.clone()),
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Runs a headless command, returning the process exit code
+pub fn run(args: &[String]) -> i32 {
+    let result = Args::parse(args).and_then(|args| {
+        if args.help {
+            println!("{USAGE}");
+            return Ok(());
+        }
+
+        run_command(&args)
+    });
+
+    match result {
+        Ok(()) => 0,
+        Err(err) if err.is::<UsageError>() => {
+            eprintln!("error: {err}\n\n{USAGE}");
+            2
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            1
+        }
+    }
+}
+
+fn run_command(args: &Args) -> anyhow::Result<()> {
+    let command: Vec<&str> = args.positional.iter().map(|s| s.as_str()).collect();
+
+    match command.as_slice() {
+        ["projects", "list"] => projects_list(args),
+        ["items", "list"] => items_list(args),
+        ["item", "set", item, field, value] => item_set(args, item, field, value),
+        ["item", "add", title] => item_add(args, title),
+        ["fields", "list"] => fields_list(args),
+        ["export"] => export_items(args),
+        ["import", path] => import_items(args, path),
+        ["logout"] => logout(args),
+        [] => Err(usage_error("No command given")),
+        _ => Err(usage_error(&format!("Unknown command \"{}\"", command.join(" ")))),
+    }
+}
+
+/// Account picked with `--account`, or the current one
+fn account(args: &Args, config: &Config) -> anyhow::Result<Account> {
+    match args.option("account") {
+        Some(name) => config
+            .accounts()
+            .into_iter()
+            .find(|a| a.name == name)
+            .ok_or_else(|| anyhow!("No account named \"{name}\" in the config")),
+        None => Ok(config.account()),
+    }
+}
+
+/// Token of the account's saved login, asking for the passphrase if it is
+/// encrypted and not set in the environment
+fn saved_token(account: &str, encrypt: bool) -> anyhow::Result<String> {
+    let cred = match credentials::load(account, encrypt)? {
+        Some(Saved::Plain(cred)) => cred,
+        Some(Saved::Locked(locked)) => {
+            let passphrase = match credentials::env_passphrase() {
+                Some(passphrase) => passphrase,
+                None => rpassword::prompt_password("Passphrase: ")?,
+            };
+            locked.unlock(&passphrase)?
+        }
+        None => return Err(anyhow!("Not logged in, run the editor once to log in or set GH_TOKEN")),
+    };
+
+    Ok(cred.token)
+}
+
+fn logout(args: &Args) -> anyhow::Result<()> {
+    let config: Config = confy::load("projects-tui", "config").unwrap_or_default();
+    let account = account(args, &config)?;
+
+    match credentials::delete(&account.name)? {
+        true => println!("Logged out of {}", account.name),
+        false => println!("Not logged in to {}", account.name),
+    }
+    Ok(())
+}
+
+/// Everything a command needs to talk to one project
+pub struct Session {
+    pub auth: Auth,
+    /// Login of the account, for `@me` in filters
+    pub login: String,
+    pub projects: Vec<Project>,
+    pub project: usize,
+}
+
+impl Session {
+    pub fn load(args: &Args) -> anyhow::Result<Session> {
+        let config: Config = confy::load("projects-tui", "config").unwrap_or_default();
+        let account = account(args, &config)?;
+
+        // Tokens from outside the app stand in for the first account, as in the editor
+        let external = match account.name == config.accounts()[0].name {
+            true => credentials::find_token(args.option("token-file"), &account.host)?,
+            false => None,
+        };
+        let token = match external {
+            Some(login) => login.credential.token,
+            None => saved_token(&account.name, config.encrypt_credentials)?,
+        };
+
+        let auth = Auth {
+            token,
+            host: account.host.clone(),
+        };
+        if let Some(problem) = github::check_token(&auth)? {
+            return Err(anyhow!("{problem}, start the editor to log in again"));
+        }
+
+        let user = github::get_user(&auth)?;
+        let mut projects = github::get_project_ids(&auth, &user.login)?;
+
+        let project = match (args.option("project"), &account.project) {
+            (Some(name), _) => app::find_named_project(&auth, &mut projects, name, &account.name)?,
+            (None, Some(saved)) => match app::find_saved_project(&auth, &mut projects, saved, &account.name)? {
+                Some(index) => index,
+                None => {
+                    eprintln!("The project {}/{} no longer exists, using the first one", saved.owner, saved.title);
+                    0
+                }
+            },
+            (None, None) => config.project_state,
+        };
+
+        if project >= projects.len() {
+            return Err(anyhow!("No project selected, use --project"));
+        }
+
+        Ok(Session {
+            auth,
+            login: user.login,
+            projects,
+            project,
+        })
+    }
+
+    pub fn project_id(&self) -> &str {
+        &self.projects[self.project].id
+    }
+
+    pub fn fields(&self) -> anyhow::Result<Vec<Field>> {
+        github::fetch_project_fields(&self.auth, self.project_id())
+    }
+
+    pub fn items(&self) -> anyhow::Result<Vec<Item>> {
+        github::fetch_project_items(&self.auth, self.project_id())
+    }
+
+    /// Items matching the `--filter` option
+    pub fn filtered_items(&self, args: &Args, fields: &[Field]) -> anyhow::Result<Vec<Item>> {
+        let filter = Filter::parse(args.option("filter").unwrap_or("")).with_user(&self.login);
+
+        Ok(self
+            .items()?
+            .into_iter()
+            .filter(|i| filter.matches(i, fields))
+            .collect())
+    }
+}
+
+fn projects_list(args: &Args) -> anyhow::Result<()> {
+    let session = Session::load(args)?;
+
+    if args.json {
+        let projects: Vec<Value> = session
+            .projects
+            .iter()
+            .map(|p| json!({ "id": p.id, "title": p.title, "owner": p.owner.login }))
+            .collect();
+        return print_json(&Value::Array(projects));
+    }
+
+    let rows = session
+        .projects
+        .iter()
+        .map(|p| vec![p.title.clone(), p.owner.login.clone(), p.id.clone()])
+        .collect();
+
+    print_table(&["Title", "Owner", "ID"], rows);
+    Ok(())
+}
+
+fn items_list(args: &Args) -> anyhow::Result<()> {
+    let session = Session::load(args)?;
+    let fields = session.fields()?;
+    let items = session.filtered_items(args, &fields)?;
+
+    if args.json {
+        let fields: Vec<&Field> = fields.iter().collect();
+        println!("{}", export::export(&items, &fields, ExportFormat::Json)?);
+        return Ok(());
+    }
+
+    let mut headers = vec!["ID"];
+    headers.extend(fields.iter().map(|f| f.get_name()));
+
+    let rows = items
+        .iter()
+        .map(|item| {
+            let mut row = vec![item.id.clone()];
+            row.extend(
+                fields
+                    .iter()
+                    .map(|f| item.field_values.name_from_field(f.get_name())),
+            );
+            row
+        })
+        .collect();
+
+    print_table(&headers, rows);
+    Ok(())
+}
+
+fn export_items(args: &Args) -> anyhow::Result<()> {
+    let session = Session::load(args)?;
+    let fields = session.fields()?;
+    let items = session.filtered_items(args, &fields)?;
+
+    let output = args.option("output");
+    let format = match (args.option("format"), output) {
+        (Some(format), _) => ExportFormat::from_name(format)?,
+        (None, Some(path)) => ExportFormat::from_path(path)?,
+        (None, None) => ExportFormat::Csv,
+    };
+
+    let config: Config = confy::load("projects-tui", "config").unwrap_or_default();
+    let fields = config.visible_fields(session.project_id(), &fields);
+    let exported = export::export(&items, &fields, format)?;
+
+    match output {
+        Some(path) => std::fs::write(path, exported)?,
+        None => print!("{exported}"),
+    }
+
+    Ok(())
+}
+
+fn import_items(args: &Args, path: &str) -> anyhow::Result<()> {
+    let format = match args.option("format") {
+        Some(format) => ExportFormat::from_name(format)?,
+        None => ExportFormat::from_path(path)?,
+    };
+
+    let rows = import::read_rows(path, format)?;
+    let session = Session::load(args)?;
+    let plan = import::plan(rows, &session.fields()?);
+
+    print!("{}", plan.report());
+
+    if !plan.errors.is_empty() {
+        return Err(anyhow!("{} rows can't be imported, nothing was changed", plan.errors.len()));
+    }
+
+    if args.dry_run {
+        return Ok(());
+    }
+
+    let failed = plan.apply(&session.auth, session.project_id(), |row, result| match result {
+        Ok(item) => println!("Row {}: added {}", row.row, item.id),
+        Err(err) => eprintln!("Row {}: failed, {err}", row.row),
+    });
+
+    if failed > 0 {
+        return Err(anyhow!("{failed} of {} rows failed", plan.rows.len()));
+    }
+
+    Ok(())
+}
+
+fn item_set(args: &Args, item: &str, field: &str, value: &str) -> anyhow::Result<()> {
+    let session = Session::load(args)?;
+    let fields = session.fields()?;
+    let items = session.items()?;
+
+    let item = find_item(&items, item)?;
+    let field = fields
+        .iter()
+        .find(|f| f.get_name().to_lowercase() == field.to_lowercase())
+        .ok_or_else(|| anyhow!("No field named \"{field}\""))?;
+
+    let value = FieldValue::parse(field, value)?;
+    github::update_item_field(&session.auth, session.project_id(), &item.id, field.get_id(), &value)?;
+
+    if args.json {
+        return print_json(&json!({ "id": item.id, "field": field.get_name(), "value": value.display() }));
+    }
+
+    println!("{}: {} = {}", item.title(), field.get_name(), value.display());
+    Ok(())
+}
+
+fn item_add(args: &Args, title: &str) -> anyhow::Result<()> {
+    let session = Session::load(args)?;
+    let item = github::add_draft_issue(&session.auth, session.project_id(), "", title)?;
+
+    if args.json {
+        return print_json(&json!({ "id": item.id, "title": title }));
+    }
+
+    println!("{}", item.id);
+    Ok(())
+}
+
+fn fields_list(args: &Args) -> anyhow::Result<()> {
+    let session = Session::load(args)?;
+    let fields = session.fields()?;
+
+    if args.json {
+        let fields: Vec<Value> = fields
+            .iter()
+            .map(|f| {
+                json!({
+                    "id": f.get_id(),
+                    "name": f.get_name(),
+                    "type": f.get_type(),
+                    "options": field_options(f),
+                })
+            })
+            .collect();
+        return print_json(&Value::Array(fields));
+    }
+
+    let rows = fields
+        .iter()
+        .map(|f| {
+            vec![
+                f.get_name().to_string(),
+                f.get_type().to_string(),
+                field_options(f).join(", "),
+            ]
+        })
+        .collect();
+
+    print_table(&["Name", "Type", "Options"], rows);
+    Ok(())
+}
+
+/// Finds an item by node ID, or failing that by its title
+pub fn find_item<'a>(items: &'a [Item], query: &str) -> anyhow::Result<&'a Item> {
+    if let Some(item) = items.iter().find(|i| i.id == query) {
+        return Ok(item);
+    }
+
+    let matches: Vec<&Item> = items
+        .iter()
+        .filter(|i| i.title().to_lowercase() == query.to_lowercase())
+        .collect();
+
+    match matches.as_slice() {
+        [item] => Ok(item),
+        [] => Err(anyhow!("No item with ID or title \"{query}\"")),
+        _ => Err(anyhow!("{} items are titled \"{query}\", use the item ID", matches.len())),
+    }
+}
+
+fn field_options(field: &Field) -> Vec<String> {
+    match field {
+        Field::ProjectV2SingleSelectField(field) => {
+            field.options.iter().map(|o| o.name.clone()).collect()
+        }
+        Field::ProjectV2IterationField(field) => field
+            .configuration
+            .iterations
+            .iter()
+            .map(|i| i.title.clone())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn print_json(value: &Value) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn print_table(headers: &[&str], rows: Vec<Vec<String>>) {
+    let mut widths: Vec<usize> = headers.iter().map(|h| text::width(h)).collect();
+
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(text::width(cell));
+        }
+    }
+
+    let print_row = |cells: Vec<&str>| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| text::pad(cell, *width, ' '))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    };
+
+    print_row(headers.to_vec());
+    for row in &rows {
+        print_row(row.iter().map(|s| s.as_str()).collect());
+    }
+}
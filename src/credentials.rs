@@ -0,0 +1,335 @@
+//! Where the GitHub token comes from. A token given with `--token-file`, in
+//! `GH_TOKEN`/`GITHUB_TOKEN` or by the gh CLI is used as is, otherwise the
+//! app logs in through the browser.
+//!
+//! That login is kept between runs, one per account. It lives next to the
+//! config rather than in the directory the app was started from, readable
+//! only by the user, and can be encrypted with a passphrase
+//! (`encrypt_credentials = true`).
+
+use anyhow::anyhow;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use github_device_flow::Credential;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+/// Passphrase used instead of asking, for scripts
+pub const PASSPHRASE_VAR: &str = "PROJECTS_TUI_PASSPHRASE";
+
+/// Where older versions saved the login
+const LEGACY_PATH: &str = "./access_token";
+
+/// Account used when none are set up in the config, its login is saved where
+/// it was before accounts existed
+pub const DEFAULT_ACCOUNT: &str = "default";
+
+const TOKEN_VARS: [&str; 2] = ["GH_TOKEN", "GITHUB_TOKEN"];
+
+/// Where the token in use came from
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenSource {
+    TokenFile(PathBuf),
+    Env(&'static str),
+    GhCli,
+    Saved,
+    DeviceFlow,
+}
+
+impl fmt::Display for TokenSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenSource::TokenFile(path) => write!(f, "{}", path.display()),
+            TokenSource::Env(var) => write!(f, "{var}"),
+            TokenSource::GhCli => write!(f, "gh CLI"),
+            TokenSource::Saved => write!(f, "saved login"),
+            TokenSource::DeviceFlow => write!(f, "browser login"),
+        }
+    }
+}
+
+/// A token in use and where it came from
+#[derive(Debug, Clone)]
+pub struct Login {
+    pub credential: Credential,
+    pub source: TokenSource,
+}
+
+/// Host entry of the gh CLI's `hosts.yml`
+#[derive(Deserialize)]
+struct GhHost {
+    oauth_token: Option<String>,
+}
+
+/// A token that doesn't need the app's own login, checked in order:
+/// `--token-file`, `GH_TOKEN`, `GITHUB_TOKEN`, then the gh CLI for `host`
+pub fn find_token(token_file: Option<&str>, host: &str) -> anyhow::Result<Option<Login>> {
+    if let Some(path) = token_file {
+        let token = fs::read_to_string(path).map_err(|err| anyhow!("Couldn't read {path}: {err}"))?;
+        let token = token.trim();
+
+        if token.is_empty() {
+            return Err(anyhow!("{path} is empty"));
+        }
+        return Ok(Some(Login::from_token(token, TokenSource::TokenFile(PathBuf::from(path)))));
+    }
+
+    for var in TOKEN_VARS {
+        if let Some(token) = std::env::var(var).ok().filter(|t| !t.trim().is_empty()) {
+            return Ok(Some(Login::from_token(token.trim(), TokenSource::Env(var))));
+        }
+    }
+
+    Ok(gh_cli_token(host).map(|token| Login::from_token(&token, TokenSource::GhCli)))
+}
+
+impl Login {
+    /// A bare token, which has no expiry or refresh token
+    fn from_token(token: &str, source: TokenSource) -> Login {
+        Login {
+            credential: Credential {
+                token: token.to_string(),
+                expiry: String::new(),
+                refresh_token: String::new(),
+            },
+            source,
+        }
+    }
+}
+
+/// Token from `gh auth login`. Newer versions of gh keep it in the system
+/// keyring instead of `hosts.yml`, so gh is asked for it when it isn't there.
+fn gh_cli_token(host: &str) -> Option<String> {
+    let from_file = gh_hosts_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_yaml::from_str::<HashMap<String, GhHost>>(&content).ok())
+        .and_then(|mut hosts| hosts.remove(host))
+        .and_then(|host| host.oauth_token)
+        .filter(|token| !token.is_empty());
+
+    from_file.or_else(|| {
+        let output = Command::new("gh")
+            .args(["auth", "token", "--hostname", host])
+            .output()
+            .ok()?;
+
+        let token = String::from_utf8(output.stdout).ok()?.trim().to_string();
+        (output.status.success() && !token.is_empty()).then_some(token)
+    })
+}
+
+/// Same lookup gh uses for its config directory
+fn gh_hosts_path() -> Option<PathBuf> {
+    let var = |name: &str| std::env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from);
+
+    let dir = var("GH_CONFIG_DIR")
+        .or_else(|| var("XDG_CONFIG_HOME").map(|d| d.join("gh")))
+        .or_else(|| match cfg!(windows) {
+            true => var("APPDATA").map(|d| d.join("GitHub CLI")),
+            false => None,
+        })
+        .or_else(|| var("HOME").map(|d| d.join(".config").join("gh")))?;
+
+    Some(dir.join("hosts.yml"))
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Stored {
+    Encrypted {
+        salt: String,
+        nonce: String,
+        ciphertext: String,
+    },
+    Plain(Credential),
+}
+
+/// A saved login, which may need a passphrase before it can be used
+pub enum Saved {
+    Plain(Credential),
+    Locked(Locked),
+}
+
+pub struct Locked {
+    salt: Vec<u8>,
+    nonce: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+impl Locked {
+    pub fn unlock(&self, passphrase: &str) -> anyhow::Result<Credential> {
+        let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &self.salt)?);
+
+        if self.nonce.len() != 12 {
+            return Err(anyhow!("Saved login is damaged, run `logout` and log in again"));
+        }
+
+        let plain = cipher
+            .decrypt(Nonce::from_slice(&self.nonce), self.ciphertext.as_slice())
+            .map_err(|_| anyhow!("Wrong passphrase"))?;
+
+        Ok(serde_json::from_slice(&plain)?)
+    }
+}
+
+/// File an account's login is saved in, inside the config directory
+pub fn path(account: &str) -> anyhow::Result<PathBuf> {
+    // The name goes into the file name, so it mustn't lead out of the directory
+    if account.is_empty() || account.contains(['/', '\\', '\0']) || account.contains("..") {
+        return Err(anyhow!("\"{account}\" can't be an account name, use one without / \\ or .."));
+    }
+
+    let config = confy::get_configuration_file_path("projects-tui", "config")?;
+    let dir = config
+        .parent()
+        .ok_or_else(|| anyhow!("No config directory found"))?;
+
+    Ok(match account {
+        DEFAULT_ACCOUNT => dir.join("credentials.json"),
+        account => dir.join(format!("credentials-{account}.json")),
+    })
+}
+
+/// The saved login of an account, if there is one. A login left in the
+/// working directory by older versions is moved to the config directory
+/// first. When logins are encrypted it is left where it is until saved
+/// again with a passphrase.
+pub fn load(account: &str, encrypt: bool) -> anyhow::Result<Option<Saved>> {
+    let path = path(account)?;
+
+    if !path.exists() {
+        if account != DEFAULT_ACCOUNT {
+            return Ok(None);
+        }
+
+        match fs::read_to_string(LEGACY_PATH).ok().and_then(|c| serde_json::from_str(&c).ok()) {
+            Some(cred) if encrypt => return Ok(Some(Saved::Plain(cred))),
+            Some(cred) => save(account, &cred, None)?,
+            None => return Ok(None),
+        }
+    }
+
+    let stored: Stored = serde_json::from_str(&fs::read_to_string(&path)?)
+        .map_err(|_| anyhow!("Saved login is damaged, run `logout` and log in again"))?;
+
+    Ok(Some(match stored {
+        Stored::Plain(cred) => Saved::Plain(cred),
+        Stored::Encrypted {
+            salt,
+            nonce,
+            ciphertext,
+        } => Saved::Locked(Locked {
+            salt: STANDARD.decode(salt)?,
+            nonce: STANDARD.decode(nonce)?,
+            ciphertext: STANDARD.decode(ciphertext)?,
+        }),
+    }))
+}
+
+/// Saves the login, encrypted when given a passphrase
+pub fn save(account: &str, cred: &Credential, passphrase: Option<&str>) -> anyhow::Result<()> {
+    let stored = match passphrase {
+        Some(passphrase) => {
+            let mut salt = [0u8; 16];
+            OsRng.fill_bytes(&mut salt);
+
+            let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+            let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+            let ciphertext = cipher
+                .encrypt(&nonce, serde_json::to_vec(cred)?.as_slice())
+                .map_err(|_| anyhow!("Couldn't encrypt the login"))?;
+
+            Stored::Encrypted {
+                salt: STANDARD.encode(salt),
+                nonce: STANDARD.encode(nonce),
+                ciphertext: STANDARD.encode(ciphertext),
+            }
+        }
+        None => Stored::Plain(cred.clone()),
+    };
+
+    write_private(&path(account)?, &serde_json::to_string(&stored)?)?;
+
+    // The login now lives in the config directory
+    if account == DEFAULT_ACCOUNT && Path::new(LEGACY_PATH).exists() {
+        fs::remove_file(LEGACY_PATH)?;
+    }
+    Ok(())
+}
+
+/// Deletes an account's saved login, returning whether there was one
+pub fn delete(account: &str) -> anyhow::Result<bool> {
+    let mut deleted = false;
+
+    let mut paths = vec![path(account)?];
+    if account == DEFAULT_ACCOUNT {
+        paths.push(PathBuf::from(LEGACY_PATH));
+    }
+
+    for path in paths {
+        if path.exists() {
+            fs::remove_file(path)?;
+            deleted = true;
+        }
+    }
+
+    Ok(deleted)
+}
+
+/// Whether a login from the device flow is past its expiry. Tokens without
+/// one, like those from other sources, never expire here.
+pub fn is_expired(cred: &Credential) -> bool {
+    match OffsetDateTime::parse(&cred.expiry, &Rfc3339) {
+        Ok(expiry) => expiry <= OffsetDateTime::now_utc(),
+        Err(_) => false,
+    }
+}
+
+/// Passphrase from the environment, if set
+pub fn env_passphrase() -> Option<String> {
+    std::env::var(PASSPHRASE_VAR).ok().filter(|p| !p.is_empty())
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> anyhow::Result<Key> {
+    let mut key = Key::default();
+    scrypt::scrypt(passphrase.as_bytes(), salt, &scrypt::Params::recommended(), &mut key)
+        .map_err(|err| anyhow!("Couldn't derive a key from the passphrase: {err}"))?;
+
+    Ok(key)
+}
+
+/// Writes a file only the user can read
+fn write_private(path: &PathBuf, contents: &str) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)?;
+
+    // The mode above is only used when the file is created
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+
+    file.write_all(contents.as_bytes())?;
+    Ok(())
+}
@@ -0,0 +1,217 @@
+//! Dates typed into the calendar, either as `YYYY-MM-DD` or relative to today
+//! like `tomorrow`, `+3d`, `next fri` or `eow`.
+
+use anyhow::anyhow;
+use time::format_description::BorrowedFormatItem;
+use time::macros::format_description;
+use time::{Date, Duration, Month, OffsetDateTime, UtcOffset, Weekday};
+
+/// How GitHub writes dates, `YYYY-MM-DD`
+pub const FORMAT: &[BorrowedFormatItem<'static>] = format_description!("[year]-[month]-[day]");
+
+/// Offset of the local timezone, or UTC when it can't be found. The time
+/// crate only finds it while the process has a single thread, so this is
+/// read at the start of `main`.
+pub fn local_offset() -> UtcOffset {
+    UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC)
+}
+
+/// Today in the timezone at `offset`
+pub fn today(offset: UtcOffset) -> Date {
+    OffsetDateTime::now_utc().to_offset(offset).date()
+}
+
+/// Reads a typed date. Relative dates are counted from `today`:
+///
+/// - `today`, `tomorrow`, `yesterday`
+/// - `+3d`, `-2w`, `+1m`, `+1y` (the sign can be left out)
+/// - `fri`, `next fri` for the coming weekday, `next week`, `next month`
+/// - `eow`, `eom`, `eoy` for the last day of the week, month or year
+pub fn parse(input: &str, today: Date) -> anyhow::Result<Date> {
+    let input = input.trim().to_lowercase();
+    let input = input.as_str();
+
+    if let Ok(date) = Date::parse(input, FORMAT) {
+        return Ok(date);
+    }
+
+    let date = match input {
+        "today" | "now" => Some(today),
+        "tomorrow" => today.next_day(),
+        "yesterday" => today.previous_day(),
+        "next week" => today.checked_add(Duration::weeks(1)),
+        "next month" => add_months(today, 1),
+        "next year" => add_months(today, 12),
+        "eow" => Some(upcoming(today, Weekday::Sunday, true)),
+        "eom" => Some(last_of_month(today)),
+        "eoy" => today.replace_month(Month::December).ok().and_then(|d| d.replace_day(31).ok()),
+        _ => match input.strip_prefix("next ").unwrap_or(input) {
+            other if weekday(other).is_some() => weekday(other).map(|day| upcoming(today, day, false)),
+            other => offset(today, other),
+        },
+    };
+
+    date.ok_or_else(|| anyhow!("\"{input}\" is not a date, try YYYY-MM-DD, +3d or next fri"))
+}
+
+/// An offset such as `+3d` or `-2w`
+fn offset(today: Date, input: &str) -> Option<Date> {
+    let unit = input.chars().last()?;
+    let amount: i64 = input[..input.len() - unit.len_utf8()].parse().ok()?;
+
+    match unit {
+        'd' => today.checked_add(Duration::days(amount)),
+        'w' => today.checked_add(Duration::weeks(amount)),
+        'm' => add_months(today, amount),
+        'y' => add_months(today, amount * 12),
+        _ => None,
+    }
+}
+
+fn weekday(name: &str) -> Option<Weekday> {
+    let days = [
+        ("monday", Weekday::Monday),
+        ("tuesday", Weekday::Tuesday),
+        ("wednesday", Weekday::Wednesday),
+        ("thursday", Weekday::Thursday),
+        ("friday", Weekday::Friday),
+        ("saturday", Weekday::Saturday),
+        ("sunday", Weekday::Sunday),
+    ];
+
+    // Any abbreviation of at least three letters, e.g. fri or thurs
+    days.iter()
+        .find(|(full, _)| name.len() >= 3 && full.starts_with(name))
+        .map(|(_, day)| *day)
+}
+
+/// The next `day` after `today`, or today itself if `inclusive`
+fn upcoming(today: Date, day: Weekday, inclusive: bool) -> Date {
+    let from_monday = |day: Weekday| day.number_days_from_monday() as i64;
+
+    let mut days = (from_monday(day) - from_monday(today.weekday())).rem_euclid(7);
+    if days == 0 && !inclusive {
+        days = 7;
+    }
+
+    today.saturating_add(Duration::days(days))
+}
+
+/// Moves by whole months, keeping the day where the month is long enough
+fn add_months(date: Date, months: i64) -> Option<Date> {
+    let index = date.year() as i64 * 12 + date.month() as i64 - 1 + months;
+    let year = i32::try_from(index.div_euclid(12)).ok()?;
+    let month = Month::try_from(index.rem_euclid(12) as u8 + 1).ok()?;
+
+    let first = Date::from_calendar_date(year, month, 1).ok()?;
+    let day = date.day().min(last_of_month(first).day());
+    first.replace_day(day).ok()
+}
+
+fn last_of_month(date: Date) -> Date {
+    let mut last = date;
+    while let Some(next) = last.next_day() {
+        if next.month() != date.month() {
+            break;
+        }
+        last = next;
+    }
+    last
+}
+
+/// Day under a cell of the calendar widget showing `shown`'s month, counted
+/// from the top left inside its border. Rows under the month and weekday
+/// headers hold a week each, starting on the Sunday before the 1st, and every
+/// day takes three cells.
+pub fn calendar_date_at(shown: Date, column: u16, row: u16) -> Option<Date> {
+    let week = row.checked_sub(2)? as i64;
+    let weekday = (column / 3) as i64;
+    if weekday > 6 {
+        return None;
+    }
+
+    let first = shown.replace_day(1).ok()?;
+    let start = first.saturating_sub(Duration::days(first.weekday().number_days_from_sunday() as i64));
+    let date = start.checked_add(Duration::days(week * 7 + weekday))?;
+
+    // Weeks after the month aren't drawn
+    let last_drawn = upcoming(last_of_month(shown), Weekday::Saturday, true);
+    (date <= last_drawn).then_some(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::date;
+
+    // A Friday
+    const TODAY: Date = date!(2024 - 03 - 15);
+
+    fn parsed(input: &str, today: Date) -> Date {
+        parse(input, today).unwrap()
+    }
+
+    #[test]
+    fn exact_and_named_dates() {
+        assert_eq!(parsed("2024-07-04", TODAY), date!(2024 - 07 - 04));
+        assert_eq!(parsed(" Today ", TODAY), TODAY);
+        assert_eq!(parsed("tomorrow", TODAY), date!(2024 - 03 - 16));
+        assert_eq!(parsed("yesterday", TODAY), date!(2024 - 03 - 14));
+        assert_eq!(parsed("next week", TODAY), date!(2024 - 03 - 22));
+    }
+
+    #[test]
+    fn offsets() {
+        assert_eq!(parsed("+3d", TODAY), date!(2024 - 03 - 18));
+        assert_eq!(parsed("3d", TODAY), date!(2024 - 03 - 18));
+        assert_eq!(parsed("-2w", TODAY), date!(2024 - 03 - 01));
+        assert_eq!(parsed("+1y", TODAY), date!(2025 - 03 - 15));
+        assert!(parse("+3x", TODAY).is_err());
+        assert!(parse("+d", TODAY).is_err());
+    }
+
+    #[test]
+    fn months_keep_the_day_where_they_can() {
+        assert_eq!(parsed("+1m", date!(2024 - 01 - 31)), date!(2024 - 02 - 29));
+        assert_eq!(parsed("+1m", date!(2023 - 01 - 31)), date!(2023 - 02 - 28));
+        assert_eq!(parsed("-1m", date!(2024 - 03 - 31)), date!(2024 - 02 - 29));
+        assert_eq!(parsed("+1m", date!(2024 - 12 - 31)), date!(2025 - 01 - 31));
+        assert_eq!(parsed("-1m", date!(2024 - 01 - 15)), date!(2023 - 12 - 15));
+        assert_eq!(parsed("+1y", date!(2024 - 02 - 29)), date!(2025 - 02 - 28));
+        assert_eq!(parsed("next month", date!(2024 - 08 - 31)), date!(2024 - 09 - 30));
+    }
+
+    #[test]
+    fn weekdays_are_always_ahead() {
+        assert_eq!(parsed("fri", TODAY), date!(2024 - 03 - 22));
+        assert_eq!(parsed("next fri", TODAY), date!(2024 - 03 - 22));
+        assert_eq!(parsed("mon", TODAY), date!(2024 - 03 - 18));
+        assert_eq!(parsed("thurs", TODAY), date!(2024 - 03 - 21));
+        assert!(parse("fr", TODAY).is_err());
+    }
+
+    #[test]
+    fn ends_of_periods() {
+        let sunday = date!(2024 - 03 - 17);
+        assert_eq!(parsed("eow", sunday), sunday);
+        assert_eq!(parsed("eow", TODAY), sunday);
+        assert_eq!(parsed("eom", date!(2024 - 02 - 10)), date!(2024 - 02 - 29));
+        assert_eq!(parsed("eoy", TODAY), date!(2024 - 12 - 31));
+        assert_eq!(parsed("eoy", date!(2024 - 12 - 31)), date!(2024 - 12 - 31));
+    }
+
+    #[test]
+    fn calendar_cells() {
+        // March 2024 starts on a Friday and ends on a Sunday
+        let shown = TODAY;
+        assert_eq!(calendar_date_at(shown, 0, 0), None);
+        assert_eq!(calendar_date_at(shown, 0, 1), None);
+        assert_eq!(calendar_date_at(shown, 0, 2), Some(date!(2024 - 02 - 25)));
+        assert_eq!(calendar_date_at(shown, 15, 2), Some(date!(2024 - 03 - 01)));
+        assert_eq!(calendar_date_at(shown, 20, 2), Some(date!(2024 - 03 - 02)));
+        assert_eq!(calendar_date_at(shown, 21, 2), None);
+        assert_eq!(calendar_date_at(shown, 0, 7), Some(date!(2024 - 03 - 31)));
+        assert_eq!(calendar_date_at(shown, 20, 7), Some(date!(2024 - 04 - 06)));
+        assert_eq!(calendar_date_at(shown, 0, 8), None);
+    }
+}
@@ -0,0 +1,353 @@
+use crate::text;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use unicode_segmentation::UnicodeSegmentation;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VimMode {
+    Insert,
+    Normal,
+    /// `d` was pressed and is waiting for a motion
+    Delete,
+}
+
+/// Single line text editor shared by field editing and prompts. Supports
+/// readline style bindings, and optionally a vim normal mode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineEditor {
+    text: String,
+    /// Cursor position in graphemes
+    cursor: usize,
+    /// `None` when vim mode is turned off
+    vim: Option<VimMode>,
+    /// Previously entered values, oldest first
+    history: Vec<String>,
+    history_index: Option<usize>,
+    /// Text being typed before browsing the history
+    draft: String,
+}
+
+/// Whether the editor used a key, unused keys such as Enter are left to the
+/// caller
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EditResult {
+    Handled,
+    Ignored,
+}
+
+impl LineEditor {
+    pub fn new(text: &str) -> LineEditor {
+        LineEditor {
+            text: text.to_string(),
+            cursor: text::grapheme_count(text),
+            vim: None,
+            history: Vec::new(),
+            history_index: None,
+            draft: String::new(),
+        }
+    }
+
+    pub fn with_vim(mut self, vim: bool) -> LineEditor {
+        self.vim = vim.then_some(VimMode::Insert);
+        self
+    }
+
+    pub fn with_history(mut self, history: Vec<String>) -> LineEditor {
+        self.history = history;
+        self
+    }
+
+    /// Replaces the text, moving the cursor to the end
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.cursor = self.len();
+        self.clamp_normal_cursor();
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Width of the text on screen
+    pub fn width(&self) -> usize {
+        text::width(&self.text)
+    }
+
+    /// Cells between the start of the text and the cursor
+    pub fn cursor_width(&self) -> usize {
+        text::cursor_width(&self.text, self.cursor)
+    }
+
+    pub fn vim_mode(&self) -> Option<VimMode> {
+        self.vim
+    }
+
+    /// Handles a key, `kill` holds text cut by Ctrl-w, Ctrl-k and the like so
+    /// it can be yanked back in any editor
+    pub fn handle_key(&mut self, key: KeyEvent, kill: &mut String) -> EditResult {
+        match self.vim {
+            Some(VimMode::Normal) => self.normal_key(key, kill),
+            Some(VimMode::Delete) => self.delete_motion_key(key, kill),
+            _ => self.insert_key(key, kill),
+        }
+    }
+
+    fn insert_key(&mut self, key: KeyEvent, kill: &mut String) -> EditResult {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+
+        match key.code {
+            KeyCode::Esc if self.vim.is_some() => {
+                self.vim = Some(VimMode::Normal);
+                self.clamp_normal_cursor();
+            }
+
+            // Movement
+            KeyCode::Char('a') if ctrl => self.cursor = 0,
+            KeyCode::Char('e') if ctrl => self.cursor = self.len(),
+            KeyCode::Char('b') if ctrl => self.move_left(),
+            KeyCode::Char('f') if ctrl => self.move_right(),
+            KeyCode::Char('b') if alt => self.cursor = self.word_start_before(),
+            KeyCode::Char('f') if alt => self.cursor = self.word_end_after(),
+            KeyCode::Left if ctrl => self.cursor = self.word_start_before(),
+            KeyCode::Right if ctrl => self.cursor = self.word_end_after(),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.len(),
+            KeyCode::Left => self.move_left(),
+            KeyCode::Right => self.move_right(),
+
+            // Killing and yanking
+            KeyCode::Char('w') if ctrl => *kill = self.cut(self.word_start_before(), self.cursor),
+            KeyCode::Backspace if alt => *kill = self.cut(self.word_start_before(), self.cursor),
+            KeyCode::Char('d') if alt => *kill = self.cut(self.cursor, self.word_end_after()),
+            KeyCode::Char('u') if ctrl => *kill = self.cut(0, self.cursor),
+            KeyCode::Char('k') if ctrl => *kill = self.cut(self.cursor, self.len()),
+            KeyCode::Char('y') if ctrl => self.insert_str(&kill.clone()),
+
+            // Deleting
+            KeyCode::Backspace => {
+                self.cursor = text::remove_before(&mut self.text, self.cursor);
+            }
+            KeyCode::Delete => {
+                self.cut(self.cursor, self.cursor + 1);
+            }
+            KeyCode::Char('d') if ctrl => {
+                self.cut(self.cursor, self.cursor + 1);
+            }
+
+            // History
+            KeyCode::Up => self.history_back(),
+            KeyCode::Down => self.history_forward(),
+
+            KeyCode::Char(_) if ctrl || alt => return EditResult::Ignored,
+            KeyCode::Char(c) => self.cursor = text::insert_char(&mut self.text, self.cursor, c),
+
+            _ => return EditResult::Ignored,
+        }
+
+        EditResult::Handled
+    }
+
+    fn normal_key(&mut self, key: KeyEvent, kill: &mut String) -> EditResult {
+        match key.code {
+            KeyCode::Char('h') | KeyCode::Left => self.move_left(),
+            KeyCode::Char('l') | KeyCode::Right => self.move_right(),
+            KeyCode::Char('0') | KeyCode::Char('^') | KeyCode::Home => self.cursor = 0,
+            KeyCode::Char('$') | KeyCode::End => self.cursor = self.len(),
+            KeyCode::Char('w') => self.cursor = self.word_start_after(),
+            KeyCode::Char('e') => self.cursor = self.word_end_after().saturating_sub(1),
+            KeyCode::Char('b') => self.cursor = self.word_start_before(),
+            KeyCode::Char('k') | KeyCode::Up => self.history_back(),
+            KeyCode::Char('j') | KeyCode::Down => self.history_forward(),
+
+            KeyCode::Char('x') | KeyCode::Delete => *kill = self.cut(self.cursor, self.cursor + 1),
+            KeyCode::Char('X') => *kill = self.cut(self.cursor.saturating_sub(1), self.cursor),
+            KeyCode::Char('D') => *kill = self.cut(self.cursor, self.len()),
+            KeyCode::Char('d') => self.vim = Some(VimMode::Delete),
+            KeyCode::Char('p') => {
+                self.move_right_past_end();
+                self.insert_str(&kill.clone());
+                self.move_left();
+            }
+            KeyCode::Char('P') => {
+                self.insert_str(&kill.clone());
+                self.move_left();
+            }
+
+            KeyCode::Char('i') => self.vim = Some(VimMode::Insert),
+            KeyCode::Char('a') => {
+                self.move_right_past_end();
+                self.vim = Some(VimMode::Insert);
+            }
+            KeyCode::Char('I') => {
+                self.cursor = 0;
+                self.vim = Some(VimMode::Insert);
+            }
+            KeyCode::Char('A') => {
+                self.cursor = self.len();
+                self.vim = Some(VimMode::Insert);
+            }
+            KeyCode::Char('C') => {
+                *kill = self.cut(self.cursor, self.len());
+                self.vim = Some(VimMode::Insert);
+            }
+            KeyCode::Char('S') => {
+                *kill = self.cut(0, self.len());
+                self.vim = Some(VimMode::Insert);
+            }
+
+            _ => return EditResult::Ignored,
+        }
+
+        self.clamp_normal_cursor();
+        EditResult::Handled
+    }
+
+    fn delete_motion_key(&mut self, key: KeyEvent, kill: &mut String) -> EditResult {
+        match key.code {
+            KeyCode::Char('d') => *kill = self.cut(0, self.len()),
+            KeyCode::Char('w') => *kill = self.cut(self.cursor, self.word_start_after()),
+            KeyCode::Char('e') => *kill = self.cut(self.cursor, self.word_end_after()),
+            KeyCode::Char('b') => *kill = self.cut(self.word_start_before(), self.cursor),
+            KeyCode::Char('0') | KeyCode::Char('^') => *kill = self.cut(0, self.cursor),
+            KeyCode::Char('$') => *kill = self.cut(self.cursor, self.len()),
+            _ => {}
+        }
+
+        self.vim = Some(VimMode::Normal);
+        self.clamp_normal_cursor();
+        EditResult::Handled
+    }
+
+    fn len(&self) -> usize {
+        text::grapheme_count(&self.text)
+    }
+
+    fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.len());
+    }
+
+    /// Moves right even onto the end of the line in normal mode
+    fn move_right_past_end(&mut self) {
+        if !self.text.is_empty() {
+            self.cursor = (self.cursor + 1).min(self.len());
+        }
+    }
+
+    /// In normal mode the cursor sits on a character rather than after it
+    fn clamp_normal_cursor(&mut self) {
+        if self.vim == Some(VimMode::Normal) {
+            self.cursor = self.cursor.min(self.len().saturating_sub(1));
+        }
+    }
+
+    fn insert_str(&mut self, s: &str) {
+        let index = text::byte_index(&self.text, self.cursor);
+        self.text.insert_str(index, s);
+        self.cursor += text::grapheme_count(s);
+    }
+
+    /// Removes the graphemes between two positions and returns them
+    fn cut(&mut self, start: usize, end: usize) -> String {
+        let end = end.min(self.len());
+        if start >= end {
+            return String::new();
+        }
+
+        let start_byte = text::byte_index(&self.text, start);
+        let end_byte = text::byte_index(&self.text, end);
+        let removed = self.text[start_byte..end_byte].to_string();
+
+        self.text.replace_range(start_byte..end_byte, "");
+        self.cursor = start;
+        removed
+    }
+
+    fn is_word(grapheme: &str) -> bool {
+        grapheme.chars().any(|c| c.is_alphanumeric() || c == '_')
+    }
+
+    /// Start of the word before the cursor, skipping any spaces first
+    fn word_start_before(&self) -> usize {
+        let graphemes: Vec<&str> = self.text.graphemes(true).collect();
+        let mut i = self.cursor.min(graphemes.len());
+
+        while i > 0 && !Self::is_word(graphemes[i - 1]) {
+            i -= 1;
+        }
+        while i > 0 && Self::is_word(graphemes[i - 1]) {
+            i -= 1;
+        }
+
+        i
+    }
+
+    /// End of the word after the cursor, skipping any spaces first
+    fn word_end_after(&self) -> usize {
+        let graphemes: Vec<&str> = self.text.graphemes(true).collect();
+        let mut i = self.cursor;
+
+        while i < graphemes.len() && !Self::is_word(graphemes[i]) {
+            i += 1;
+        }
+        while i < graphemes.len() && Self::is_word(graphemes[i]) {
+            i += 1;
+        }
+
+        i
+    }
+
+    /// Start of the next word, as moved to by vim's `w`
+    fn word_start_after(&self) -> usize {
+        let graphemes: Vec<&str> = self.text.graphemes(true).collect();
+        let mut i = self.cursor;
+
+        while i < graphemes.len() && Self::is_word(graphemes[i]) {
+            i += 1;
+        }
+        while i < graphemes.len() && !Self::is_word(graphemes[i]) {
+            i += 1;
+        }
+
+        i
+    }
+
+    fn history_back(&mut self) {
+        let index = match self.history_index {
+            None if self.history.is_empty() => return,
+            None => {
+                self.draft = self.text.clone();
+                self.history.len() - 1
+            }
+            Some(0) => return,
+            Some(i) => i - 1,
+        };
+
+        self.set_history(Some(index));
+    }
+
+    fn history_forward(&mut self) {
+        match self.history_index {
+            Some(i) if i + 1 < self.history.len() => self.set_history(Some(i + 1)),
+            Some(_) => self.set_history(None),
+            None => {}
+        }
+    }
+
+    fn set_history(&mut self, index: Option<usize>) {
+        self.history_index = index;
+        self.text = match index {
+            Some(i) => self.history[i].clone(),
+            None => self.draft.clone(),
+        };
+        self.cursor = self.len();
+        self.clamp_normal_cursor();
+    }
+}
@@ -0,0 +1,122 @@
+use crate::project::*;
+use anyhow::anyhow;
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub fn from_name(name: &str) -> anyhow::Result<ExportFormat> {
+        match name.to_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            "md" | "markdown" => Ok(ExportFormat::Markdown),
+            _ => Err(anyhow!("Unknown format \"{name}\", expected csv, json or md")),
+        }
+    }
+
+    /// Guesses the format from a file extension
+    pub fn from_path(path: &str) -> anyhow::Result<ExportFormat> {
+        let extension = std::path::Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .ok_or_else(|| anyhow!("Can't tell the format of \"{path}\", add .csv, .json or .md"))?;
+
+        ExportFormat::from_name(extension)
+    }
+}
+
+/// Renders items as a table with one column per field. Issues and pull
+/// requests get an extra URL column.
+pub fn export(items: &[Item], fields: &[&Field], format: ExportFormat) -> anyhow::Result<String> {
+    let with_url = items.iter().any(|i| i.url().is_some());
+
+    let mut headers: Vec<String> = fields.iter().map(|f| f.get_name().to_string()).collect();
+    if with_url {
+        headers.push(String::from("URL"));
+    }
+
+    match format {
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            writer.write_record(&headers)?;
+
+            for item in items {
+                writer.write_record(row(item, fields, with_url))?;
+            }
+
+            Ok(String::from_utf8(writer.into_inner()?)?)
+        }
+
+        ExportFormat::Json => {
+            let rows: Vec<Value> = items
+                .iter()
+                .map(|item| {
+                    let mut object = Map::new();
+                    object.insert(String::from("id"), Value::String(item.id.clone()));
+                    object.insert(String::from("type"), Value::String(item.item_type.clone()));
+
+                    for field in fields {
+                        object.insert(field.get_name().to_string(), json_value(item, field));
+                    }
+
+                    if with_url {
+                        object.insert(
+                            String::from("URL"),
+                            item.url().map_or(Value::Null, |u| Value::String(u.to_string())),
+                        );
+                    }
+
+                    Value::Object(object)
+                })
+                .collect();
+
+            Ok(serde_json::to_string_pretty(&rows)?)
+        }
+
+        ExportFormat::Markdown => {
+            let mut table = markdown_row(&headers);
+            table.push_str(&markdown_row(&vec![String::from("---"); headers.len()]));
+
+            for item in items {
+                table.push_str(&markdown_row(&row(item, fields, with_url)));
+            }
+
+            Ok(table)
+        }
+    }
+}
+
+fn row(item: &Item, fields: &[&Field], with_url: bool) -> Vec<String> {
+    let mut row: Vec<String> = fields
+        .iter()
+        .map(|f| item.field_values.name_from_field(f.get_name()))
+        .collect();
+
+    if with_url {
+        row.push(item.url().unwrap_or_default().to_string());
+    }
+
+    row
+}
+
+fn json_value(item: &Item, field: &Field) -> Value {
+    match item.field_values.get_from_field(field.get_name()) {
+        ProjectV2ItemField::Empty(_) => Value::Null,
+        ProjectV2ItemField::NumberValue { number, field: _ } => serde_json::json!(number),
+        value => Value::String(value.value()),
+    }
+}
+
+fn markdown_row(cells: &[String]) -> String {
+    let cells: Vec<String> = cells
+        .iter()
+        .map(|c| c.replace('|', "\\|").replace('\n', " "))
+        .collect();
+
+    format!("| {} |\n", cells.join(" | "))
+}
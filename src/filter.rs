@@ -0,0 +1,238 @@
+use crate::project::*;
+
+/// A single term of a filter query
+#[derive(Debug, Clone, PartialEq)]
+enum Term {
+    /// `field:a,b` matches when the field equals any of the values
+    Field { field: String, values: Vec<String> },
+    /// `has:field` matches when the field has a value
+    Has(String),
+    /// `no:field` matches when the field is empty
+    No(String),
+    /// `is:open`, `is:closed`, `is:draft`, `is:issue`, `is:pr` or `is:archived`
+    Is(String),
+    /// Bare words are searched for in the title
+    Text(String),
+}
+
+/// Filter query in the style of the GitHub web UI, e.g.
+/// `status:Todo,"In Progress" -priority:Low no:Estimate bug`. Qualifiers
+/// that aren't known here, like `label:`, match every item so that views
+/// saved on GitHub still open.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Filter {
+    query: String,
+    terms: Vec<(bool, Term)>,
+}
+
+impl Filter {
+    pub fn parse(query: &str) -> Filter {
+        let terms = split_query(query)
+            .into_iter()
+            .map(|word| {
+                let (negated, word) = match word.strip_prefix('-') {
+                    Some(rest) if !rest.is_empty() => (true, rest.to_string()),
+                    _ => (false, word),
+                };
+
+                let term = match word.split_once(':') {
+                    Some(("has", field)) => Term::Has(field.to_lowercase()),
+                    Some(("no", field)) => Term::No(field.to_lowercase()),
+                    Some(("is", state)) => Term::Is(state.to_lowercase()),
+                    Some((field, values)) if !field.is_empty() => Term::Field {
+                        field: field.to_lowercase(),
+                        values: values.split(',').map(|v| v.to_lowercase()).collect(),
+                    },
+                    _ => Term::Text(word.to_lowercase()),
+                };
+
+                (negated, term)
+            })
+            .collect();
+
+        Filter {
+            query: query.to_string(),
+            terms,
+        }
+    }
+
+    /// Reads `@me` in values as the given login
+    pub fn with_user(mut self, login: &str) -> Filter {
+        for (_, term) in &mut self.terms {
+            if let Term::Field { values, .. } = term {
+                for value in values.iter_mut().filter(|v| *v == "@me") {
+                    *value = login.to_lowercase();
+                }
+            }
+        }
+        self
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn matches(&self, item: &Item, fields: &[Field]) -> bool {
+        self.terms.iter().all(|(negated, term)| {
+            let matched = match term {
+                Term::Field { field, values } => field_values(item, fields, field)
+                    .map(|found| found.iter().any(|v| values.contains(&v.to_lowercase()))),
+                Term::Has(field) => field_values(item, fields, field).map(|found| !found.is_empty()),
+                Term::No(field) => field_values(item, fields, field).map(|found| found.is_empty()),
+                Term::Is(state) => is_state(item, state),
+                Term::Text(text) => Some(item.title().to_lowercase().contains(text)),
+            };
+
+            matched.is_none_or(|matched| matched != *negated)
+        })
+    }
+}
+
+/// Values of a field by case insensitive name. `assignee` gives the logins of
+/// the issue or pull request assignees. None when there is no such field.
+fn field_values(item: &Item, fields: &[Field], name: &str) -> Option<Vec<String>> {
+    if let Some(field) = fields.iter().find(|f| f.get_name().to_lowercase() == name) {
+        let value = item.field_values.name_from_field(field.get_name());
+        return Some(if value.is_empty() { vec![] } else { vec![value] });
+    }
+
+    match name {
+        "assignee" | "assignees" => Some(item.assignees()),
+        _ => None,
+    }
+}
+
+/// Whether the item is in an `is:` state, None for states not known here.
+/// Drafts can't be closed, so they count as open.
+fn is_state(item: &Item, state: &str) -> Option<bool> {
+    match state {
+        "open" => Some(item.closed_at().is_none()),
+        "closed" => Some(item.closed_at().is_some()),
+        "draft" => Some(item.item_type == "DRAFT_ISSUE"),
+        "issue" => Some(item.item_type == "ISSUE"),
+        "pr" => Some(item.item_type == "PULL_REQUEST"),
+        "archived" => Some(item.is_archived),
+        _ => None,
+    }
+}
+
+/// Splits a query on whitespace, keeping quoted sections together
+fn split_query(query: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in query.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    words.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn status() -> Field {
+        serde_json::from_value(json!({
+            "id": "F1",
+            "name": "Status",
+            "dataType": "SINGLE_SELECT",
+            "options": [
+                { "id": "O1", "name": "Todo", "color": "GRAY", "description": "" },
+                { "id": "O2", "name": "Done", "color": "GREEN", "description": "" },
+            ],
+        }))
+        .unwrap()
+    }
+
+    fn issue(title: &str, state: &str, assignee: &str, closed: bool) -> Item {
+        let Field::ProjectV2SingleSelectField(field) = status() else {
+            unreachable!()
+        };
+        serde_json::from_value(json!({
+            "id": title,
+            "type": "ISSUE",
+            "fieldValues": { "nodes": [{ "name": state, "field": field }] },
+            "content": {
+                "id": title,
+                "title": title,
+                "url": "https://github.com/o/r/issues/1",
+                "assignees": { "nodes": [{ "login": assignee }] },
+                "closedAt": if closed { json!("2024-01-02T00:00:00Z") } else { json!(null) },
+            },
+        }))
+        .unwrap()
+    }
+
+    fn draft(title: &str) -> Item {
+        serde_json::from_value(json!({
+            "id": title,
+            "type": "DRAFT_ISSUE",
+            "content": { "title": title, "body": "" },
+        }))
+        .unwrap()
+    }
+
+    fn matching(query: &str, items: &[Item]) -> Vec<String> {
+        let filter = Filter::parse(query).with_user("Octocat");
+        items
+            .iter()
+            .filter(|item| filter.matches(item, &[status()]))
+            .map(|item| item.id.clone())
+            .collect()
+    }
+
+    fn items() -> Vec<Item> {
+        vec![
+            issue("open", "Todo", "octocat", false),
+            issue("closed", "Done", "hubot", true),
+            draft("draft"),
+        ]
+    }
+
+    #[test]
+    fn fields_and_negation() {
+        assert_eq!(matching("status:todo", &items()), ["open"]);
+        assert_eq!(matching("-status:Done", &items()), ["open", "draft"]);
+        assert_eq!(matching("no:status", &items()), ["draft"]);
+        assert_eq!(matching("dra", &items()), ["draft"]);
+    }
+
+    #[test]
+    fn is_qualifiers() {
+        assert_eq!(matching("is:open", &items()), ["open", "draft"]);
+        assert_eq!(matching("is:closed", &items()), ["closed"]);
+        assert_eq!(matching("is:issue -is:closed", &items()), ["open"]);
+        assert_eq!(matching("is:draft", &items()), ["draft"]);
+        assert!(matching("is:pr", &items()).is_empty());
+    }
+
+    #[test]
+    fn me_is_the_current_user() {
+        assert_eq!(matching("assignee:@me", &items()), ["open"]);
+        assert_eq!(matching("-assignee:@me is:issue", &items()), ["closed"]);
+    }
+
+    #[test]
+    fn unknown_qualifiers_match_everything() {
+        let all = ["open", "closed", "draft"];
+        assert_eq!(matching("label:bug", &items()), all);
+        assert_eq!(matching("-label:bug", &items()), all);
+        assert_eq!(matching("has:milestone is:unknown", &items()), all);
+        assert_eq!(matching("label:bug is:open status:Todo", &items()), ["open"]);
+    }
+}
@@ -3,7 +3,10 @@ use reqwest::blocking::Response;
 use serde::Serialize;
 use serde_json::Value;
 
+use crate::history::FieldValue;
 use crate::project::*;
+use crate::sort::Sort;
+use crate::view::View;
 
 #[derive(Debug, Serialize)]
 struct AccessTokenRequest {
@@ -15,25 +18,55 @@ struct AccessTokenRequest {
 
 use serde_json::from_value;
 
-pub fn send_query_request(token: &str, query: &str) -> anyhow::Result<Response> {
+/// A token and the GitHub host it belongs to
+#[derive(Debug, Clone)]
+pub struct Auth {
+    pub token: String,
+    pub host: String,
+}
+
+impl Auth {
+    /// Root of the REST API, GitHub Enterprise serves it under `/api/v3`
+    fn api_url(&self) -> String {
+        match self.host.as_str() {
+            "github.com" => String::from("https://api.github.com"),
+            host => format!("https://{host}/api/v3"),
+        }
+    }
+
+    fn graphql_url(&self) -> String {
+        match self.host.as_str() {
+            "github.com" => String::from("https://api.github.com/graphql"),
+            host => format!("https://{host}/api/graphql"),
+        }
+    }
+}
+
+pub fn send_query_request(auth: &Auth, query: &str) -> anyhow::Result<Response> {
+    send_query_with_variables(auth, query, serde_json::json!({}))
+}
+
+/// Sends a query taking `$name` variables. Text typed by users goes through
+/// these rather than into the query, so quotes and newlines can't break it.
+pub fn send_query_with_variables(auth: &Auth, query: &str, variables: Value) -> anyhow::Result<Response> {
     let client = reqwest::blocking::Client::new();
 
     // Make the POST request
     Ok(client
-        .post("https://api.github.com/graphql")
-        .header(reqwest::header::AUTHORIZATION, format!("Bearer {}", token))
+        .post(auth.graphql_url())
+        .header(reqwest::header::AUTHORIZATION, format!("Bearer {}", auth.token))
         .header(reqwest::header::USER_AGENT, "Projects TUI")
-        .json(&serde_json::json!({ "query": query }))
+        .json(&serde_json::json!({ "query": query, "variables": variables }))
         .send()?)
 }
 
-pub fn get_user(token: &str) -> anyhow::Result<User> {
+pub fn get_user(auth: &Auth) -> anyhow::Result<User> {
     let client = reqwest::blocking::Client::new();
 
     let response = client
-        .get("https://api.github.com/user")
+        .get(format!("{}/user", auth.api_url()))
         .header("Accept", "application/vnd.github+json")
-        .header("Authorization", format!("Bearer {}", token))
+        .header("Authorization", format!("Bearer {}", auth.token))
         .header("X-GitHub-Api-Version", "2022-11-28")
         .header("User-Agent", "Projects-TUI")
         .send()?;
@@ -41,11 +74,82 @@ pub fn get_user(token: &str) -> anyhow::Result<User> {
     Ok(response.json::<User>()?)
 }
 
-pub fn get_project_ids(token: &str, login: &str) -> Result<Vec<Project>, anyhow::Error> {
-    let query =
-        "{user(login: \"USER\") {projectsV2(first: 20) {nodes {id title}}}}".replace("USER", login);
+/// Scopes a classic token needs to read and edit projects
+pub const REQUIRED_SCOPES: [&str; 1] = ["project"];
+
+/// Why a token can't be used
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenProblem {
+    /// GitHub turned it down, usually because it was revoked
+    Rejected,
+    /// Past the expiry given by the device flow, with no way to refresh it
+    Expired,
+    /// Accepted, but without access to projects
+    MissingScopes { missing: Vec<String>, granted: Vec<String> },
+}
+
+impl std::fmt::Display for TokenProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenProblem::Rejected => write!(f, "GitHub rejected the token, it may have been revoked"),
+            TokenProblem::Expired => write!(f, "The login has expired"),
+            TokenProblem::MissingScopes { missing, granted } => {
+                let granted = match granted.is_empty() {
+                    true => String::from("none"),
+                    false => granted.join(", "),
+                };
+                write!(f, "The token is missing the {} scope (it has {granted})", missing.join(", "))
+            }
+        }
+    }
+}
+
+/// Checks a token with the cheapest request there is. Classic tokens list
+/// their scopes in `X-OAuth-Scopes`, fine-grained ones don't and pass as long
+/// as they are accepted.
+pub fn check_token(auth: &Auth) -> anyhow::Result<Option<TokenProblem>> {
+    let client = reqwest::blocking::Client::new();
+
+    let response = client
+        .get(format!("{}/user", auth.api_url()))
+        .header("Accept", "application/vnd.github+json")
+        .header("Authorization", format!("Bearer {}", auth.token))
+        .header("X-GitHub-Api-Version", "2022-11-28")
+        .header("User-Agent", "Projects-TUI")
+        .send()?;
+
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        return Ok(Some(TokenProblem::Rejected));
+    }
+
+    let Some(scopes) = response.headers().get("X-OAuth-Scopes") else {
+        return Ok(None);
+    };
+
+    let granted: Vec<String> = scopes
+        .to_str()?
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    let missing: Vec<String> = REQUIRED_SCOPES
+        .iter()
+        .filter(|s| !granted.iter().any(|g| g == *s))
+        .map(|s| s.to_string())
+        .collect();
+
+    Ok((!missing.is_empty()).then_some(TokenProblem::MissingScopes { missing, granted }))
+}
+
+/// Fields of a project fetched for the project list
+const PROJECT_FIELDS: &str = "id title owner {... on User {login} ... on Organization {login}}";
 
-    let res = send_query_request(token, &query)?;
+pub fn get_project_ids(auth: &Auth, login: &str) -> Result<Vec<Project>, anyhow::Error> {
+    let query = "{user(login: \"USER\") {projectsV2(first: 20) {nodes {PROJECT}}}}"
+        .replace("USER", login)
+        .replace("PROJECT", PROJECT_FIELDS);
+
+    let res = send_query_request(auth, &query)?;
 
     let res_json = res.json::<serde_json::Value>()?;
 
@@ -65,8 +169,55 @@ pub fn get_project_ids(token: &str, login: &str) -> Result<Vec<Project>, anyhow:
         .collect())
 }
 
+/// A project by its node ID, `None` if it was deleted or can't be seen
+pub fn get_project(auth: &Auth, project_id: &str) -> anyhow::Result<Option<Project>> {
+    let query = "{node(id: \"PROJECT_ID\") {... on ProjectV2 {PROJECT}}}"
+        .replace("PROJECT_ID", project_id)
+        .replace("PROJECT", PROJECT_FIELDS);
+
+    let res_json = send_query_request(auth, &query)?.json::<Value>()?;
+
+    // A deleted project is a null node with a NOT_FOUND error, anything else
+    // going wrong is an error
+    let errors = res_json.get("errors").and_then(|v| v.as_array());
+    if let Some(error) = errors.into_iter().flatten().find(|e| e["type"] != "NOT_FOUND") {
+        return Err(anyhow!(error["message"].as_str().unwrap_or("GitHub returned an error").to_string()));
+    }
+
+    let node = res_json.get("data").and_then(|v| v.get("node")).ok_or_else(|| {
+        anyhow!(res_json["message"].as_str().unwrap_or("GitHub sent no data").to_string())
+    })?;
+
+    // Other kinds of node come back as an empty object
+    Ok(from_value::<Project>(node.clone()).ok())
+}
+
+/// A project of any user or organization by its title
+pub fn find_project(auth: &Auth, owner: &str, title: &str) -> anyhow::Result<Option<Project>> {
+    let query = "query($owner: String!, $title: String) {repositoryOwner(login: $owner) \
+        {... on ProjectV2Owner {projectsV2(first: 20, query: $title) {nodes {PROJECT}}}}}"
+        .replace("PROJECT", PROJECT_FIELDS);
+    let variables = serde_json::json!({ "owner": owner, "title": title });
+
+    let res_json = send_query_with_variables(auth, &query, variables)?.json::<Value>()?;
+
+    let nodes = res_json
+        .get("data")
+        .and_then(|v| v.get("repositoryOwner"))
+        .and_then(|v| v.get("projectsV2"))
+        .and_then(|v| v.get("nodes"))
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow!("No user or organization named \"{owner}\""))?;
+
+    // The search also matches parts of titles
+    Ok(nodes
+        .iter()
+        .filter_map(|v| from_value::<Project>(v.clone()).ok())
+        .find(|p| p.title.to_lowercase() == title.to_lowercase()))
+}
+
 /// Returns all fields that a project has
-pub fn fetch_project_fields(token: &str, project_id: &str) -> Result<Vec<Field>, anyhow::Error> {
+pub fn fetch_project_fields(auth: &Auth, project_id: &str) -> Result<Vec<Field>, anyhow::Error> {
     let query = r#"
             query {
                 node(id: "PROJECT_ID") {
@@ -82,6 +233,7 @@ pub fn fetch_project_fields(token: &str, project_id: &str) -> Result<Vec<Field>,
                                             startDate
                                             id
                                             title
+                                            duration
                                         }
                                     }
                                 }
@@ -109,7 +261,7 @@ pub fn fetch_project_fields(token: &str, project_id: &str) -> Result<Vec<Field>,
         "#.replace("PROJECT_ID", project_id);
 
     let _client = reqwest::blocking::Client::new();
-    let response = send_query_request(&token, &query)?;
+    let response = send_query_request(auth, &query)?;
     let response_json: Value = response.json()?;
 
     let nodes = rip_data(&response_json, "fields");
@@ -117,7 +269,80 @@ pub fn fetch_project_fields(token: &str, project_id: &str) -> Result<Vec<Field>,
     Ok(serde_json::from_value(nodes.clone())?)
 }
 
-pub fn fetch_project_items(token: &str, project_id: &str) -> anyhow::Result<Vec<Item>> {
+/// The views set up for a project on GitHub
+pub fn fetch_project_views(auth: &Auth, project_id: &str) -> anyhow::Result<Vec<View>> {
+    let field_name = "... on ProjectV2FieldCommon {name}";
+    let query = r#"
+            query {
+                node(id: "PROJECT_ID") {
+                    ... on ProjectV2 {
+                        views(first: 20) {
+                            nodes {
+                                name
+                                layout
+                                filter
+                                sortByFields(first: 5) { nodes { direction field { FIELD_NAME } } }
+                                groupByFields(first: 1) { nodes { FIELD_NAME } }
+                                visibleFields(first: 50) { nodes { FIELD_NAME } }
+                            }
+                        }
+                    }
+                }
+            }
+        "#
+    .replace("PROJECT_ID", project_id)
+    .replace("FIELD_NAME", field_name);
+
+    let res_json = send_query_request(auth, &query)?.json::<Value>()?;
+
+    let nodes = res_json
+        .get("data")
+        .and_then(|v| v.get("node"))
+        .and_then(|v| v.get("views"))
+        .and_then(|v| v.get("nodes"))
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow!("JSON object did not contain views"))?;
+
+    let text = |v: &Value, key: &str| v.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    let connection = |v: &Value, key: &str| -> Vec<Value> {
+        v.get(key)
+            .and_then(|v| v.get("nodes"))
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default()
+    };
+
+    Ok(nodes
+        .iter()
+        .map(|view| View {
+            name: text(view, "name"),
+            filter: text(view, "filter"),
+            sort: connection(view, "sortByFields")
+                .iter()
+                .filter_map(|s| {
+                    let field = s.get("field").map(|f| text(f, "name")).filter(|f| !f.is_empty())?;
+                    Some(Sort {
+                        field,
+                        descending: text(s, "direction") == "DESC",
+                    })
+                })
+                .collect(),
+            group_by: connection(view, "groupByFields")
+                .first()
+                .map(|f| text(f, "name"))
+                .filter(|f| !f.is_empty()),
+            fields: connection(view, "visibleFields")
+                .iter()
+                .map(|f| text(f, "name"))
+                .filter(|f| !f.is_empty())
+                .collect(),
+            layout: text(view, "layout"),
+            local: false,
+        })
+        .collect())
+}
+
+pub fn fetch_project_items(auth: &Auth, project_id: &str) -> anyhow::Result<Vec<Item>> {
     let query = r#"
         query {
             node(id: "PROJECT_ID") {
@@ -171,6 +396,7 @@ pub fn fetch_project_items(token: &str, project_id: &str) -> anyhow::Result<Vec<
                                                         startDate
                                                         id
                                                         title
+                                                        duration
                                                     }
                                                 }
                                             }
@@ -204,6 +430,36 @@ pub fn fetch_project_items(token: &str, project_id: &str) -> anyhow::Result<Vec<
                                 }
                             }
                             type
+                            isArchived
+                            content {
+                                ... on DraftIssue {
+                                    id
+                                    title
+                                    body
+                                }
+                                ... on Issue {
+                                    id
+                                    title
+                                    url
+                                    closedAt
+                                    assignees(first: 10) {
+                                        nodes {
+                                            login
+                                        }
+                                    }
+                                }
+                                ... on PullRequest {
+                                    id
+                                    title
+                                    url
+                                    closedAt
+                                    assignees(first: 10) {
+                                        nodes {
+                                            login
+                                        }
+                                    }
+                                }
+                            }
                         }
                     }
                 }
@@ -211,12 +467,14 @@ pub fn fetch_project_items(token: &str, project_id: &str) -> anyhow::Result<Vec<
         }"#
     .replace("PROJECT_ID", project_id);
 
-    let response = send_query_request(token, &query)?;
+    let response = send_query_request(auth, &query)?;
     let response_json = response.json::<Value>()?;
 
     let nodes = rip_data(&response_json, "items");
+    let items: Vec<Item> = serde_json::from_value(nodes.clone())?;
 
-    Ok(serde_json::from_value(nodes.clone())?)
+    // Archived items are still returned by the API, but hidden in the web UI
+    Ok(items.into_iter().filter(|i| !i.is_archived).collect())
 }
 
 pub fn rip_data<'a>(value: &'a Value, path: &'a str) -> &'a Value {
@@ -224,14 +482,12 @@ pub fn rip_data<'a>(value: &'a Value, path: &'a str) -> &'a Value {
 }
 
 pub fn update_item_number(
-    token: &str,
+    auth: &Auth,
     project_id: &str,
     item_id: &str,
     field_id: &str,
     new_number: f32,
 ) -> anyhow::Result<ItemMutation> {
-    let _client = reqwest::Client::new();
-
     let query = r#"mutation {
         updateProjectV2ItemFieldValue(
             input: {
@@ -255,29 +511,33 @@ pub fn update_item_number(
         .replace("FIELD_ID", field_id)
         .replace("NEW_TEXT", &new_number.to_string());
 
-    let response = send_query_request(token, &query)?;
+    let response = send_query_request(auth, &query)?;
     let response_json = response.json::<Value>()?;
 
+    if let Value::String(err) = &response_json["errors"][0]["message"] {
+       return Err(anyhow!(err.to_owned()));
+    }
+
     let mutation = &response_json["data"]["updateProjectV2ItemFieldValue"]["projectV2Item"];
 
     Ok(serde_json::from_value(mutation.clone())?)
 }
 
 pub fn update_item_date(
-    token: &str,
+    auth: &Auth,
     project_id: &str,
     item_id: &str,
     field_id: &str,
     new_date: &str,
 ) -> anyhow::Result<ItemMutation> {
-    let query = r#"mutation {
+    let query = r#"mutation($date: Date!) {
         updateProjectV2ItemFieldValue(
             input: {
                 projectId: "PROJECT_ID"
                 itemId: "ITEM_ID"
                 fieldId: "FIELD_ID"
                 value: {
-                    date: "NEW_TEXT"
+                    date: $date
                 }
             }
         ) {
@@ -290,32 +550,35 @@ pub fn update_item_date(
     let query = query
         .replace("PROJECT_ID", project_id)
         .replace("ITEM_ID", item_id)
-        .replace("FIELD_ID", field_id)
-        .replace("NEW_TEXT", new_date);
+        .replace("FIELD_ID", field_id);
 
-    let response = send_query_request(token, &query)?;
+    let response = send_query_with_variables(auth, &query, serde_json::json!({ "date": new_date }))?;
     let response_json = response.json::<Value>()?;
 
+    if let Value::String(err) = &response_json["errors"][0]["message"] {
+       return Err(anyhow!(err.to_owned()));
+    }
+
     let mutation = &response_json["data"]["updateProjectV2ItemFieldValue"]["projectV2Item"];
 
     Ok(serde_json::from_value(mutation.clone())?)
 }
 
 pub fn update_item_text(
-    token: &str,
+    auth: &Auth,
     project_id: &str,
     item_id: &str,
     field_id: &str,
     new_text: &str,
 ) -> anyhow::Result<ItemMutation> {
-    let query = r#"mutation {
+    let query = r#"mutation($text: String!) {
         updateProjectV2ItemFieldValue(
             input: {
                 projectId: "PROJECT_ID"
                 itemId: "ITEM_ID"
                 fieldId: "FIELD_ID"
                 value: {
-                    text: "NEW_TEXT"
+                    text: $text
                 }
             }
         ) {
@@ -328,10 +591,9 @@ pub fn update_item_text(
     let query = query
         .replace("PROJECT_ID", project_id)
         .replace("ITEM_ID", item_id)
-        .replace("FIELD_ID", field_id)
-        .replace("NEW_TEXT", new_text);
+        .replace("FIELD_ID", field_id);
 
-    let response = send_query_request(token, &query)?;
+    let response = send_query_with_variables(auth, &query, serde_json::json!({ "text": new_text }))?;
     let response_json = response.json::<Value>()?;
     
     if let Value::String(err) = &response_json["errors"][0]["message"] {
@@ -344,7 +606,7 @@ pub fn update_item_text(
 }
 
 pub fn update_item_option(
-    token: &str,
+    auth: &Auth,
     project_id: &str,
     item_id: &str,
     field_id: &str,
@@ -373,7 +635,7 @@ pub fn update_item_option(
         .replace("FIELD_ID", field_id)
         .replace("OPTION_ID", option_id);
 
-    let response = send_query_request(token, &query)?;
+    let response = send_query_request(auth, &query)?;
     let response_json = response.json::<Value>()?;
 
     if let Value::String(err) = &response_json["errors"][0]["message"] {
@@ -385,40 +647,229 @@ pub fn update_item_option(
     Ok(serde_json::from_value(mutation.clone())?)
 }
 
+/// Writes any kind of field value, clearing the field for `FieldValue::Empty`
+pub fn update_item_field(
+    auth: &Auth,
+    project_id: &str,
+    item_id: &str,
+    field_id: &str,
+    value: &FieldValue,
+) -> anyhow::Result<ItemMutation> {
+    match value {
+        FieldValue::Text(text) => update_item_text(auth, project_id, item_id, field_id, text),
+        FieldValue::Number(number) => {
+            update_item_number(auth, project_id, item_id, field_id, *number)
+        }
+        FieldValue::Date(date) => update_item_date(auth, project_id, item_id, field_id, date),
+        FieldValue::SingleSelect { option_id, name: _ } => {
+            update_item_option(auth, project_id, item_id, field_id, option_id)
+        }
+        FieldValue::Empty => clear_item_field(auth, project_id, item_id, field_id),
+    }
+}
+
 pub fn add_draft_issue(
-    token: &str,
+    auth: &Auth,
     project_id: &str,
     body: &str,
     title: &str,
 ) -> anyhow::Result<Item> {
-    let query = r#"mutation {
+    let query = r#"mutation($title: String!, $body: String) {
         addProjectV2DraftIssue(
             input: {
                 projectId: "PROJECT_ID"
-                title: "TITLE"
-                body: "BODY"
+                title: $title
+                body: $body
             }
         ) {
             projectItem {
                 id
                 type
+                content {
+                    ... on DraftIssue {
+                        id
+                        title
+                        body
+                    }
+                }
+            }
+        }
+    }"#;
+
+    let query = query.replace("PROJECT_ID", project_id);
+    let variables = serde_json::json!({ "title": title, "body": body });
+
+    let response = send_query_with_variables(auth, &query, variables)?;
+    let response_json = response.json::<Value>()?;
+
+    if let Value::String(err) = &response_json["errors"][0]["message"] {
+       return Err(anyhow!(err.to_owned()));
+    }
+
+    let mutation = &response_json["data"]["addProjectV2DraftIssue"]["projectItem"];
+
+    Ok(serde_json::from_value(mutation.clone())?)
+}
+
+pub fn add_item_by_id(auth: &Auth, project_id: &str, content_id: &str) -> anyhow::Result<Item> {
+    let query = r#"mutation {
+        addProjectV2ItemById(
+            input: {
+                projectId: "PROJECT_ID"
+                contentId: "CONTENT_ID"
+            }
+        ) {
+            item {
+                id
+                type
             }
         }
     }"#;
 
     let query = query
         .replace("PROJECT_ID", project_id)
-        .replace("TITLE", title)
-        .replace("BODY", body);
+        .replace("CONTENT_ID", content_id);
 
-    let response = send_query_request(token, &query)?;
+    let response = send_query_request(auth, &query)?;
     let response_json = response.json::<Value>()?;
 
     if let Value::String(err) = &response_json["errors"][0]["message"] {
        return Err(anyhow!(err.to_owned()));
     }
 
-    let mutation = &response_json["data"]["addProjectV2DraftIssue"]["projectItem"];
+    let mutation = &response_json["data"]["addProjectV2ItemById"]["item"];
+
+    Ok(serde_json::from_value(mutation.clone())?)
+}
+
+pub fn clear_item_field(
+    auth: &Auth,
+    project_id: &str,
+    item_id: &str,
+    field_id: &str,
+) -> anyhow::Result<ItemMutation> {
+    let query = r#"mutation {
+        clearProjectV2ItemFieldValue(
+            input: {
+                projectId: "PROJECT_ID"
+                itemId: "ITEM_ID"
+                fieldId: "FIELD_ID"
+            }
+        ) {
+            projectV2Item {
+                id
+            }
+        }
+    }"#;
+
+    let query = query
+        .replace("PROJECT_ID", project_id)
+        .replace("ITEM_ID", item_id)
+        .replace("FIELD_ID", field_id);
+
+    let response = send_query_request(auth, &query)?;
+    let response_json = response.json::<Value>()?;
+
+    if let Value::String(err) = &response_json["errors"][0]["message"] {
+       return Err(anyhow!(err.to_owned()));
+    }
+
+    let mutation = &response_json["data"]["clearProjectV2ItemFieldValue"]["projectV2Item"];
+
+    Ok(serde_json::from_value(mutation.clone())?)
+}
+
+pub fn delete_item(auth: &Auth, project_id: &str, item_id: &str) -> anyhow::Result<()> {
+    let query = r#"mutation {
+        deleteProjectV2Item(
+            input: {
+                projectId: "PROJECT_ID"
+                itemId: "ITEM_ID"
+            }
+        ) {
+            deletedItemId
+        }
+    }"#;
+
+    let query = query
+        .replace("PROJECT_ID", project_id)
+        .replace("ITEM_ID", item_id);
+
+    let response = send_query_request(auth, &query)?;
+    let response_json = response.json::<Value>()?;
+
+    if let Value::String(err) = &response_json["errors"][0]["message"] {
+       return Err(anyhow!(err.to_owned()));
+    }
+
+    Ok(())
+}
+
+/// Archives or unarchives an item, archived items are hidden from the board
+pub fn set_item_archived(
+    auth: &Auth,
+    project_id: &str,
+    item_id: &str,
+    archived: bool,
+) -> anyhow::Result<ItemMutation> {
+    let mutation_name = if archived {
+        "archiveProjectV2Item"
+    } else {
+        "unarchiveProjectV2Item"
+    };
+
+    let query = r#"mutation {
+        MUTATION(
+            input: {
+                projectId: "PROJECT_ID"
+                itemId: "ITEM_ID"
+            }
+        ) {
+            item {
+                id
+            }
+        }
+    }"#;
+
+    let query = query
+        .replace("MUTATION", mutation_name)
+        .replace("PROJECT_ID", project_id)
+        .replace("ITEM_ID", item_id);
+
+    let response = send_query_request(auth, &query)?;
+    let response_json = response.json::<Value>()?;
+
+    if let Value::String(err) = &response_json["errors"][0]["message"] {
+       return Err(anyhow!(err.to_owned()));
+    }
+
+    let mutation = &response_json["data"][mutation_name]["item"];
 
     Ok(serde_json::from_value(mutation.clone())?)
 }
+
+/// Node ID of the issue or pull request at a URL
+pub fn get_content_id(auth: &Auth, url: &str) -> anyhow::Result<String> {
+    let query = r#"query($url: URI!) {
+        resource(url: $url) {
+            ... on Issue {
+                id
+            }
+            ... on PullRequest {
+                id
+            }
+        }
+    }"#;
+
+    let response = send_query_with_variables(auth, query, serde_json::json!({ "url": url }))?;
+    let response_json = response.json::<Value>()?;
+
+    if let Value::String(err) = &response_json["errors"][0]["message"] {
+       return Err(anyhow!(err.to_owned()));
+    }
+
+    match &response_json["data"]["resource"]["id"] {
+        Value::String(id) => Ok(id.to_owned()),
+        _ => Err(anyhow!("{url} is not an issue or pull request")),
+    }
+}
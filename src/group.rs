@@ -0,0 +1,110 @@
+use crate::project::*;
+use crate::sort::Sort;
+use std::cmp::Ordering;
+use std::ops::Range;
+
+/// Types of field items can be grouped by
+pub const GROUPABLE: [&str; 4] = ["SINGLE_SELECT", "ITERATION", "TEXT", "ASSIGNEES"];
+
+/// Items next to each other in the table with the same value of the field
+/// grouped by
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    /// Empty for items without a value
+    pub value: String,
+    pub items: Range<usize>,
+}
+
+/// A row of the table, the header of a group or an item
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TableRow {
+    Group(usize),
+    Item(usize),
+}
+
+/// Whether the field is the assignees of the issues and pull requests, which
+/// aren't part of an item's field values
+fn is_assignees(field: &Field) -> bool {
+    field.get_type() == "ASSIGNEES"
+}
+
+pub fn value(item: &Item, field: &Field) -> String {
+    match is_assignees(field) {
+        true => item.assignees().join(", "),
+        false => item.field_values.name_from_field(field.get_name()),
+    }
+}
+
+/// Moves items with the same value next to each other, in the order GitHub
+/// shows the groups in. Items keep their order within a group, and those
+/// without a value come last.
+pub fn sort_into_groups(field: &Field, items: &mut [Item]) {
+    let sort = Sort {
+        field: field.get_name().to_string(),
+        descending: false,
+    };
+
+    // Values the field order ties, like finished iterations that aren't
+    // loaded or text differing in case, are still kept apart by value
+    items.sort_by(|a, b| {
+        let order = match is_assignees(field) {
+            true => compare_assignees(&value(a, field), &value(b, field)),
+            false => sort.compare(a, b),
+        };
+        order.then_with(|| value(a, field).cmp(&value(b, field)))
+    });
+}
+
+fn compare_assignees(a: &str, b: &str) -> Ordering {
+    match (a.is_empty(), b.is_empty()) {
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        _ => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
+/// Runs of items with the same value
+pub fn groups(field: &Field, items: &[Item]) -> Vec<Group> {
+    let mut groups: Vec<Group> = Vec::new();
+
+    for (index, item) in items.iter().enumerate() {
+        let value = value(item, field);
+
+        match groups.last_mut() {
+            Some(group) if group.value == value => group.items.end = index + 1,
+            _ => groups.push(Group {
+                value,
+                items: index..index + 1,
+            }),
+        }
+    }
+
+    groups
+}
+
+/// Rows of the table, each group's header followed by its items unless it is
+/// collapsed
+pub fn rows(groups: &[Group], collapsed: &[String]) -> Vec<TableRow> {
+    let mut rows = Vec::new();
+
+    for (index, group) in groups.iter().enumerate() {
+        rows.push(TableRow::Group(index));
+
+        if !collapsed.contains(&group.value) {
+            rows.extend(group.items.clone().map(TableRow::Item));
+        }
+    }
+
+    rows
+}
+
+/// Total of a number field over a group's items
+pub fn sum(items: &[Item], group: &Group, field: &Field) -> f32 {
+    items[group.items.clone()]
+        .iter()
+        .filter_map(|item| match item.field_values.get_from_field(field.get_name()) {
+            ProjectV2ItemField::NumberValue { number, .. } => Some(*number),
+            _ => None,
+        })
+        .sum()
+}
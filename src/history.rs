@@ -0,0 +1,214 @@
+use crate::dates;
+use crate::number;
+use crate::project::*;
+use anyhow::anyhow;
+use time::Date;
+
+/// The value of a single item field, in the form needed to write it back
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Text(String),
+    Number(f32),
+    Date(String),
+    SingleSelect { option_id: String, name: String },
+    Empty,
+}
+
+impl FieldValue {
+    pub fn from_item_field(value: &ProjectV2ItemField) -> FieldValue {
+        use ProjectV2ItemField::*;
+
+        match value {
+            TextValue { text, field: _ } => FieldValue::Text(text.clone()),
+            NumberValue { number, field: _ } => FieldValue::Number(*number),
+            DateValue { date, field: _ } => FieldValue::Date(date.clone()),
+            SingleSelectValue { name, field } => match field.options.iter().find(|o| &o.name == name) {
+                Some(option) => FieldValue::SingleSelect {
+                    option_id: option.id.clone(),
+                    name: option.name.clone(),
+                },
+                None => FieldValue::Empty,
+            },
+            // Iterations can't be edited yet, so there is nothing to restore
+            IterationValue { .. } | Empty(_) => FieldValue::Empty,
+        }
+    }
+
+    /// Parses user input into a value for the given field. An empty string
+    /// clears the field.
+    pub fn parse(field: &Field, input: &str) -> anyhow::Result<FieldValue> {
+        let input = input.trim();
+
+        if input.is_empty() {
+            return Ok(FieldValue::Empty);
+        }
+
+        match field {
+            Field::ProjectV2SingleSelectField(select) => select
+                .options
+                .iter()
+                .find(|o| o.name.to_lowercase() == input.to_lowercase())
+                .map(|o| FieldValue::SingleSelect {
+                    option_id: o.id.clone(),
+                    name: o.name.clone(),
+                })
+                .ok_or_else(|| {
+                    let names: Vec<&str> = select.options.iter().map(|o| o.name.as_str()).collect();
+                    anyhow!("No option \"{input}\" in {}, expected one of: {}", select.name, names.join(", "))
+                }),
+
+            _ => match field.get_type() {
+                "TEXT" | "TITLE" => Ok(FieldValue::Text(input.to_string())),
+                "NUMBER" => Ok(FieldValue::Number(number::parse(input)?)),
+                "DATE" => {
+                    let date = Date::parse(input, dates::FORMAT)
+                        .map_err(|_| anyhow!("\"{input}\" is not a date, expected YYYY-MM-DD"))?;
+                    Ok(FieldValue::Date(date.format(dates::FORMAT)?))
+                }
+                other => Err(anyhow!("{} fields can't be set", other.to_lowercase())),
+            },
+        }
+    }
+
+    /// String form used by `Nodes::set_value`
+    pub fn display(&self) -> String {
+        match self {
+            FieldValue::Text(text) => text.clone(),
+            FieldValue::Number(number) => number.to_string(),
+            FieldValue::Date(date) => date.clone(),
+            FieldValue::SingleSelect { option_id: _, name } => name.clone(),
+            FieldValue::Empty => String::new(),
+        }
+    }
+}
+
+/// A change made to the project that can be reverted
+#[derive(Debug, Clone)]
+pub enum Change {
+    Field {
+        item_id: String,
+        field_id: String,
+        old: FieldValue,
+        new: FieldValue,
+    },
+    AddItem(Item),
+    DeleteItem(Item),
+    Archive(Item),
+    Unarchive(Item),
+    /// Several changes undone and redone together
+    Batch(Vec<Change>),
+}
+
+impl Change {
+    /// The change that undoes this one
+    pub fn inverse(self) -> Change {
+        match self {
+            Change::Field {
+                item_id,
+                field_id,
+                old,
+                new,
+            } => Change::Field {
+                item_id,
+                field_id,
+                old: new,
+                new: old,
+            },
+            Change::AddItem(item) => Change::DeleteItem(item),
+            Change::DeleteItem(item) => Change::AddItem(item),
+            Change::Archive(item) => Change::Unarchive(item),
+            Change::Unarchive(item) => Change::Archive(item),
+            Change::Batch(changes) => {
+                Change::Batch(changes.into_iter().rev().map(Change::inverse).collect())
+            }
+        }
+    }
+}
+
+impl Change {
+    fn replace_item_id(&mut self, old_id: &str, new_id: &str) {
+        match self {
+            Change::Field { item_id, .. } if item_id == old_id => *item_id = new_id.to_string(),
+            Change::AddItem(item)
+            | Change::DeleteItem(item)
+            | Change::Archive(item)
+            | Change::Unarchive(item)
+                if item.id == old_id =>
+            {
+                item.id = new_id.to_string()
+            }
+            Change::Batch(changes) => {
+                for change in changes {
+                    change.replace_item_id(old_id, new_id);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A batch that failed part way, split into the changes that went through
+/// and those that didn't, so each can be kept in the history
+#[derive(Debug)]
+pub struct PartlyApplied {
+    pub applied: Change,
+    pub remaining: Change,
+    pub error: anyhow::Error,
+}
+
+impl std::fmt::Display for PartlyApplied {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let count = |change: &Change| match change {
+            Change::Batch(changes) => changes.len(),
+            _ => 1,
+        };
+
+        write!(
+            f,
+            "{}, {} of {} changes were made",
+            self.error,
+            count(&self.applied),
+            count(&self.applied) + count(&self.remaining)
+        )
+    }
+}
+
+impl std::error::Error for PartlyApplied {}
+
+/// Undo and redo stacks for changes made in the editor
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Change>,
+    redo: Vec<Change>,
+}
+
+impl History {
+    /// Records a newly made change, a fresh change invalidates the redo stack
+    pub fn record(&mut self, change: Change) {
+        self.undo.push(change);
+        self.redo.clear();
+    }
+
+    pub fn pop_undo(&mut self) -> Option<Change> {
+        self.undo.pop()
+    }
+
+    pub fn pop_redo(&mut self) -> Option<Change> {
+        self.redo.pop()
+    }
+
+    pub fn push_undo(&mut self, change: Change) {
+        self.undo.push(change);
+    }
+
+    pub fn push_redo(&mut self, change: Change) {
+        self.redo.push(change);
+    }
+
+    /// Points older changes at an item's new ID after it was re-added
+    pub fn replace_item_id(&mut self, old_id: &str, new_id: &str) {
+        for change in self.undo.iter_mut().chain(self.redo.iter_mut()) {
+            change.replace_item_id(old_id, new_id);
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use crate::export::ExportFormat;
+use crate::github::{self, Auth};
+use crate::history::FieldValue;
+use crate::project::*;
+use anyhow::anyhow;
+use serde_json::Value;
+
+/// What a row of the import creates
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    Draft(String),
+    Url(String),
+}
+
+/// A row of the import, with its values already converted for their fields
+#[derive(Debug, Clone)]
+pub struct ImportRow {
+    pub row: usize,
+    pub source: Source,
+    pub values: Vec<(Field, FieldValue)>,
+}
+
+/// Everything an import would do, checked before anything is sent to GitHub
+#[derive(Debug, Default)]
+pub struct ImportPlan {
+    pub rows: Vec<ImportRow>,
+    pub ignored_columns: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+/// Reads rows of column name and value pairs from a CSV or JSON file
+pub fn read_rows(path: &str, format: ExportFormat) -> anyhow::Result<Vec<Vec<(String, String)>>> {
+    let content = std::fs::read_to_string(path)?;
+
+    match format {
+        ExportFormat::Csv => {
+            let mut reader = csv::Reader::from_reader(content.as_bytes());
+            let headers: Vec<String> = reader.headers()?.iter().map(|h| h.to_string()).collect();
+
+            reader
+                .records()
+                .map(|record| {
+                    Ok(headers
+                        .iter()
+                        .cloned()
+                        .zip(record?.iter().map(|v| v.to_string()))
+                        .collect())
+                })
+                .collect()
+        }
+
+        ExportFormat::Json => {
+            let rows: Vec<serde_json::Map<String, Value>> = serde_json::from_str(&content)
+                .map_err(|err| anyhow!("Expected a JSON array of objects: {err}"))?;
+
+            Ok(rows
+                .into_iter()
+                .map(|row| {
+                    row.into_iter()
+                        .map(|(column, value)| {
+                            let value = match value {
+                                Value::String(s) => s,
+                                Value::Null => String::new(),
+                                other => other.to_string(),
+                            };
+                            (column, value)
+                        })
+                        .collect()
+                })
+                .collect())
+        }
+
+        ExportFormat::Markdown => Err(anyhow!("Markdown can't be imported, use csv or json")),
+    }
+}
+
+/// Matches columns to fields by name and converts every value. A `URL`
+/// column adds an existing issue or pull request instead of a draft, and
+/// columns that can't be set are ignored so exported files import again.
+pub fn plan(rows: Vec<Vec<(String, String)>>, fields: &[Field]) -> ImportPlan {
+    let mut plan = ImportPlan::default();
+
+    for (index, columns) in rows.into_iter().enumerate() {
+        let row = index + 1;
+        let mut title = None;
+        let mut url = None;
+        let mut values = Vec::new();
+
+        for (column, value) in columns {
+            if column.to_lowercase() == "url" {
+                url = Some(value.trim().to_string()).filter(|u| !u.is_empty());
+                continue;
+            }
+
+            // Columns written by an export that can't be set, such as the
+            // item's ID and type or read-only fields like Assignees
+            let field = fields
+                .iter()
+                .find(|f| f.get_name().to_lowercase() == column.to_lowercase())
+                .filter(|f| f.is_editable());
+            let read_only = ["id", "type"].contains(&column.to_lowercase().as_str());
+
+            let Some(field) = field.filter(|_| !read_only) else {
+                if !plan.ignored_columns.contains(&column) {
+                    plan.ignored_columns.push(column);
+                }
+                continue;
+            };
+
+            if field.get_type() == "TITLE" {
+                title = Some(value.trim().to_string()).filter(|t| !t.is_empty());
+                continue;
+            }
+
+            match FieldValue::parse(field, &value) {
+                Ok(FieldValue::Empty) => {}
+                Ok(value) => values.push((field.clone(), value)),
+                Err(err) => plan.errors.push(format!("Row {row}: {err}")),
+            }
+        }
+
+        let source = match (url, title) {
+            (Some(url), _) => Source::Url(url),
+            (None, Some(title)) => Source::Draft(title),
+            (None, None) => {
+                plan.errors.push(format!("Row {row}: needs a Title or URL"));
+                continue;
+            }
+        };
+
+        plan.rows.push(ImportRow {
+            row,
+            source,
+            values,
+        });
+    }
+
+    plan
+}
+
+impl ImportPlan {
+    pub fn report(&self) -> String {
+        let mut report = String::new();
+
+        for row in &self.rows {
+            match &row.source {
+                Source::Draft(title) => {
+                    report.push_str(&format!("Row {}: add draft \"{title}\"\n", row.row))
+                }
+                Source::Url(url) => report.push_str(&format!("Row {}: add {url}\n", row.row)),
+            }
+
+            for (field, value) in &row.values {
+                report.push_str(&format!("    {} = {}\n", field.get_name(), value.display()));
+            }
+        }
+
+        if !self.ignored_columns.is_empty() {
+            report.push_str(&format!(
+                "Ignored columns with no field that can be set: {}\n",
+                self.ignored_columns.join(", ")
+            ));
+        }
+
+        for error in &self.errors {
+            report.push_str(&format!("{error}\n"));
+        }
+
+        report
+    }
+
+    /// Creates the items and sets their fields, calling `progress` after each row
+    pub fn apply(
+        &self,
+        auth: &Auth,
+        project_id: &str,
+        mut progress: impl FnMut(&ImportRow, &anyhow::Result<Item>),
+    ) -> usize {
+        let mut failed = 0;
+
+        for row in &self.rows {
+            let result = self.apply_row(auth, project_id, row);
+
+            if result.is_err() {
+                failed += 1;
+            }
+
+            progress(row, &result);
+        }
+
+        failed
+    }
+
+    fn apply_row(&self, auth: &Auth, project_id: &str, row: &ImportRow) -> anyhow::Result<Item> {
+        let item = match &row.source {
+            Source::Draft(title) => github::add_draft_issue(auth, project_id, "", title)?,
+            Source::Url(url) => {
+                let content_id = github::get_content_id(auth, url)?;
+                github::add_item_by_id(auth, project_id, &content_id)?
+            }
+        };
+
+        for (field, value) in &row.values {
+            github::update_item_field(auth, project_id, &item.id, field.get_id(), value)?;
+        }
+
+        Ok(item)
+    }
+}
@@ -0,0 +1,180 @@
+//! Numbers behind the insights screen, worked out from the loaded items.
+//! Points come from a number field, or each item counts as one when the
+//! project has none.
+
+use crate::dates;
+use crate::project::*;
+use time::{Date, Duration};
+
+/// Progress through an iteration, day by day
+pub struct Burndown {
+    pub iteration: String,
+    pub start: Date,
+    /// Length of the iteration in days
+    pub days: i64,
+    /// Points of every item in the iteration
+    pub total: f64,
+    /// Points done by the end of each day from the start up to today
+    pub done: Vec<(f64, f64)>,
+}
+
+impl Burndown {
+    /// Points still to do at the end of each day
+    pub fn remaining(&self) -> Vec<(f64, f64)> {
+        self.done.iter().map(|(day, done)| (*day, self.total - done)).collect()
+    }
+
+    pub fn end(&self) -> Date {
+        self.start + Duration::days(self.days)
+    }
+}
+
+/// The number field under the cursor, or else the first one
+pub fn points_field(fields: &[Field], current: usize) -> Option<&Field> {
+    fields
+        .get(current)
+        .filter(|f| f.get_type() == "NUMBER")
+        .or_else(|| fields.iter().find(|f| f.get_type() == "NUMBER"))
+}
+
+pub fn points(item: &Item, field: Option<&Field>) -> f64 {
+    let Some(field) = field else {
+        return 1.0;
+    };
+
+    match item.field_values.get_from_field(field.get_name()) {
+        ProjectV2ItemField::NumberValue { number, .. } => *number as f64,
+        _ => 0.0,
+    }
+}
+
+/// The field named Status, or else the first single select field
+pub fn status_field(fields: &[Field]) -> Option<&Field> {
+    let selects = || fields.iter().filter(|f| f.get_type() == "SINGLE_SELECT");
+    selects()
+        .find(|f| f.get_name().eq_ignore_ascii_case("status"))
+        .or_else(|| selects().next())
+}
+
+/// Closed issues and pull requests, and items with a status of Done
+pub fn is_done(item: &Item, status: Option<&Field>) -> bool {
+    item.closed_at().is_some()
+        || status.is_some_and(|f| item.field_values.name_from_field(f.get_name()).eq_ignore_ascii_case("done"))
+}
+
+/// The day an item was done. Drafts don't say when, so those marked Done
+/// count as done today.
+fn done_on(item: &Item, status: Option<&Field>, today: Date) -> Option<Date> {
+    if !is_done(item, status) {
+        return None;
+    }
+
+    Some(item.closed_at().and_then(|closed| parse_date(closed.get(..10)?, today)).unwrap_or(today))
+}
+
+/// A `YYYY-MM-DD` date from GitHub
+fn parse_date(date: &str, today: Date) -> Option<Date> {
+    dates::parse(date, today).ok()
+}
+
+/// Burn-down of the iteration running today, or the first one if none has
+/// started yet
+pub fn burndown(fields: &[Field], items: &[Item], points_field: Option<&Field>, today: Date) -> Option<Burndown> {
+    let field = fields.iter().find_map(|f| match f {
+        Field::ProjectV2IterationField(field) => Some(field),
+        _ => None,
+    })?;
+
+    let mut iterations: Vec<(Date, &Iteration)> = field
+        .configuration
+        .iterations
+        .iter()
+        .filter_map(|i| Some((parse_date(&i.start_date, today)?, i)))
+        .collect();
+    iterations.sort_by_key(|(start, _)| *start);
+
+    let current = iterations
+        .iter()
+        .rposition(|(start, _)| *start <= today)
+        .unwrap_or(0);
+    let (start, iteration) = *iterations.get(current)?;
+
+    // GitHub gives the length, older responses only the next start
+    let days = match iteration.duration {
+        0 => iterations.get(current + 1).map_or(14, |(next, _)| (*next - start).whole_days()),
+        days => days,
+    };
+
+    let status = status_field(fields);
+    let items: Vec<&Item> = items
+        .iter()
+        .filter(|item| item.field_values.name_from_field(&field.name) == iteration.title)
+        .collect();
+    let total = items.iter().map(|item| points(item, points_field)).sum();
+
+    let elapsed = (today - start).whole_days().clamp(0, days);
+    let done = (0..=elapsed)
+        .map(|day| {
+            let end_of_day = start + Duration::days(day);
+            let done = items
+                .iter()
+                .filter(|item| done_on(item, status, today).is_some_and(|date| date <= end_of_day))
+                .map(|item| points(item, points_field))
+                .sum();
+            (day as f64, done)
+        })
+        .collect();
+
+    Some(Burndown {
+        iteration: iteration.title.clone(),
+        start,
+        days,
+        total,
+        done,
+    })
+}
+
+/// Items with each option of the status field in the order the options are
+/// set up in, then those with no status
+pub fn status_counts(field: &Field, items: &[Item]) -> Vec<(String, u64)> {
+    let Field::ProjectV2SingleSelectField(select) = field else {
+        return Vec::new();
+    };
+    let count = |name: &str| {
+        items
+            .iter()
+            .filter(|item| item.field_values.name_from_field(&select.name) == name)
+            .count() as u64
+    };
+
+    let mut counts: Vec<(String, u64)> = select.options.iter().map(|o| (o.name.clone(), count(&o.name))).collect();
+    match count("") {
+        0 => {}
+        empty => counts.push((format!("No {}", select.name), empty)),
+    }
+    counts
+}
+
+/// Points of the open items each person is assigned, most first
+pub fn workload(fields: &[Field], items: &[Item], points_field: Option<&Field>) -> Vec<(String, f64)> {
+    let status = status_field(fields);
+    let mut workload: Vec<(String, f64)> = Vec::new();
+
+    for item in items.iter().filter(|item| !is_done(item, status)) {
+        let mut assignees = item.assignees();
+        if assignees.is_empty() {
+            assignees.push(String::from("Unassigned"));
+        }
+
+        for assignee in assignees {
+            let points = points(item, points_field);
+            match workload.iter_mut().find(|(name, _)| *name == assignee) {
+                Some((_, total)) => *total += points,
+                None => workload.push((assignee, points)),
+            }
+        }
+    }
+
+    workload.sort_by(|a, b| b.1.total_cmp(&a.1));
+    workload
+}
@@ -0,0 +1,371 @@
+//! Keys bound to actions in each mode. The config only lists bindings that
+//! differ from the defaults, e.g.
+//!
+//! ```toml
+//! [keymap.normal]
+//! sort_by = ["S"]
+//! top = ["g g", "Home"]
+//! ```
+
+use crate::action::Action;
+use anyhow::anyhow;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    /// The table
+    Normal,
+    /// Option and project lists
+    List,
+    Calendar,
+    /// Text fields and prompts such as adding an item
+    Prompt,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 4] = [Mode::Normal, Mode::List, Mode::Calendar, Mode::Prompt];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::Normal => "Table",
+            Mode::List => "Lists",
+            Mode::Calendar => "Calendar",
+            Mode::Prompt => "Text input",
+        }
+    }
+
+    fn defaults(&self) -> &'static [(Action, &'static [&'static str])] {
+        match self {
+            Mode::Normal => &[
+                (Action::Up, &["k", "Up"]),
+                (Action::Down, &["j", "Down"]),
+                (Action::Left, &["h", "Left"]),
+                (Action::Right, &["l", "Right"]),
+                (Action::Top, &["g g", "Home"]),
+                (Action::Bottom, &["G", "End"]),
+                (Action::PageDown, &["Ctrl-f", "PageDown"]),
+                (Action::PageUp, &["Ctrl-b", "PageUp"]),
+                (Action::HalfPageDown, &["Ctrl-d"]),
+                (Action::HalfPageUp, &["Ctrl-u"]),
+                (Action::Edit, &["i"]),
+                (Action::AddItem, &["a"]),
+                (Action::ClearField, &["c"]),
+                (Action::DeleteItem, &["d"]),
+                (Action::ArchiveItem, &["A"]),
+                (Action::OpenItem, &["o"]),
+                (Action::Undo, &["u"]),
+                (Action::Redo, &["Ctrl-r"]),
+                (Action::ToggleSelect, &["Space"]),
+                (Action::VisualSelect, &["v"]),
+                (Action::ClearSelection, &["Esc"]),
+                (Action::Filter, &["/"]),
+                (Action::SortBy, &["s"]),
+                (Action::HideField, &["x"]),
+                (Action::ShowAllFields, &["X"]),
+                (Action::GroupBy, &["B"]),
+                (Action::ToggleGroup, &["z a", "z z"]),
+                (Action::CollapseGroups, &["z M"]),
+                (Action::ExpandGroups, &["z R"]),
+                (Action::NextView, &["Tab", "]"]),
+                (Action::PreviousView, &["Shift-Tab", "["]),
+                (Action::SaveView, &["W"]),
+                (Action::DefaultView, &[]),
+                (Action::WidenColumn, &[">"]),
+                (Action::NarrowColumn, &["<"]),
+                (Action::ResetColumnWidth, &["="]),
+                (Action::FitToTerminal, &["+"]),
+                (Action::ToggleFooter, &["F"]),
+                (Action::SwitchProject, &["p"]),
+                (Action::SwitchAccount, &["P"]),
+                (Action::Export, &["e"]),
+                (Action::CommandPalette, &[":", "Ctrl-p"]),
+                (Action::Insights, &["I"]),
+                (Action::Help, &["?"]),
+                (Action::Quit, &["q"]),
+            ],
+            Mode::List => &[
+                (Action::Up, &["k", "Up"]),
+                (Action::Down, &["j", "Down"]),
+                (Action::Confirm, &["Enter"]),
+                (Action::Cancel, &["Esc"]),
+            ],
+            Mode::Calendar => &[
+                (Action::Left, &["h", "Left"]),
+                (Action::Right, &["l", "Right"]),
+                (Action::Up, &["k", "Up"]),
+                (Action::Down, &["j", "Down"]),
+                (Action::NextMonth, &["J"]),
+                (Action::PreviousMonth, &["K"]),
+                (Action::NextYear, &["L"]),
+                (Action::PreviousYear, &["H"]),
+                (Action::Today, &["."]),
+                (Action::Confirm, &["Enter"]),
+                (Action::Cancel, &["Esc"]),
+            ],
+            Mode::Prompt => &[(Action::Confirm, &["Enter"]), (Action::Cancel, &["Esc"])],
+        }
+    }
+}
+
+/// A single key with its modifiers. Shift is left out for characters, since
+/// it is already part of the character.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyPress {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyPress {
+    pub fn from_event(key: KeyEvent) -> KeyPress {
+        let mut modifiers = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        if let KeyCode::Char(_) | KeyCode::BackTab = key.code {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+
+        KeyPress {
+            code: key.code,
+            modifiers,
+        }
+    }
+
+    /// Reads a key such as `j`, `Ctrl-f`, `Alt-Enter` or `Space`
+    pub fn parse(name: &str) -> anyhow::Result<KeyPress> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = name;
+
+        loop {
+            if let Some(r) = rest.strip_prefix("Ctrl-") {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = r;
+            } else if let Some(r) = rest.strip_prefix("Alt-") {
+                modifiers |= KeyModifiers::ALT;
+                rest = r;
+            } else if let Some(r) = rest.strip_prefix("Shift-") {
+                modifiers |= KeyModifiers::SHIFT;
+                rest = r;
+            } else {
+                break;
+            }
+        }
+
+        let code = match rest {
+            "Enter" => KeyCode::Enter,
+            "Esc" => KeyCode::Esc,
+            "Tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            "Tab" => KeyCode::Tab,
+            "Backspace" => KeyCode::Backspace,
+            "Delete" => KeyCode::Delete,
+            "Insert" => KeyCode::Insert,
+            "Up" => KeyCode::Up,
+            "Down" => KeyCode::Down,
+            "Left" => KeyCode::Left,
+            "Right" => KeyCode::Right,
+            "Home" => KeyCode::Home,
+            "End" => KeyCode::End,
+            "PageUp" => KeyCode::PageUp,
+            "PageDown" => KeyCode::PageDown,
+            "Space" => KeyCode::Char(' '),
+            f if f.len() > 1 && f.starts_with('F') => KeyCode::F(
+                f[1..]
+                    .parse()
+                    .map_err(|_| anyhow!("Unknown key \"{name}\""))?,
+            ),
+            c if c.chars().count() == 1 => {
+                let c = c.chars().next().unwrap_or_default();
+                match modifiers.contains(KeyModifiers::SHIFT) {
+                    true => KeyCode::Char(c.to_ascii_uppercase()),
+                    false => KeyCode::Char(c),
+                }
+            }
+            _ => return Err(anyhow!("Unknown key \"{name}\"")),
+        };
+
+        if let KeyCode::Char(_) | KeyCode::BackTab = code {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+
+        Ok(KeyPress { code, modifiers })
+    }
+
+    pub fn name(&self) -> String {
+        let mut name = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            name.push_str("Ctrl-");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            name.push_str("Alt-");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            name.push_str("Shift-");
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => name.push_str("Space"),
+            KeyCode::Char(c) => name.push(c),
+            KeyCode::BackTab => name.push_str("Shift-Tab"),
+            KeyCode::PageUp => name.push_str("PageUp"),
+            KeyCode::PageDown => name.push_str("PageDown"),
+            KeyCode::F(n) => name.push_str(&format!("F{n}")),
+            other => name.push_str(&format!("{other:?}")),
+        }
+
+        name
+    }
+}
+
+/// Bindings as written in the config, by mode and then action
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(default)]
+pub struct Keymap {
+    pub normal: BTreeMap<Action, Vec<String>>,
+    pub list: BTreeMap<Action, Vec<String>>,
+    pub calendar: BTreeMap<Action, Vec<String>>,
+    pub prompt: BTreeMap<Action, Vec<String>>,
+}
+
+impl Keymap {
+    fn overrides(&self, mode: Mode) -> &BTreeMap<Action, Vec<String>> {
+        match mode {
+            Mode::Normal => &self.normal,
+            Mode::List => &self.list,
+            Mode::Calendar => &self.calendar,
+            Mode::Prompt => &self.prompt,
+        }
+    }
+}
+
+/// One sequence of keys, usually a single key, and what it does
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub keys: Vec<KeyPress>,
+    pub action: Action,
+}
+
+impl Binding {
+    /// Keys as written in the config, with sequences of plain characters
+    /// written together like `gg`
+    pub fn name(&self) -> String {
+        let names: Vec<String> = self.keys.iter().map(|k| k.name()).collect();
+
+        if names.iter().all(|n| n.chars().count() == 1) {
+            names.concat()
+        } else {
+            names.join(" ")
+        }
+    }
+}
+
+pub enum Lookup {
+    Action(Action),
+    /// The keys so far start a longer binding
+    Pending,
+    None,
+}
+
+/// The keymap with defaults filled in, ready for looking up keys
+#[derive(Debug, Default)]
+pub struct Bindings {
+    normal: Vec<Binding>,
+    list: Vec<Binding>,
+    calendar: Vec<Binding>,
+    prompt: Vec<Binding>,
+}
+
+impl Bindings {
+    /// Applies the config on top of the defaults. A key bound in the config
+    /// is taken away from any default action that used it. Keys that can't
+    /// be read are skipped and returned as errors.
+    pub fn resolve(keymap: &Keymap) -> (Bindings, Vec<String>) {
+        let mut bindings = Bindings::default();
+        let mut errors = Vec::new();
+
+        for mode in Mode::ALL {
+            let overrides = keymap.overrides(mode);
+            let mut resolved = Vec::new();
+
+            for (action, keys) in overrides {
+                for keys in keys {
+                    match parse_sequence(keys) {
+                        Ok(keys) => resolved.push(Binding { keys, action: *action }),
+                        Err(err) => errors.push(format!("{}: {}: {err}", mode.name(), keys)),
+                    }
+                }
+            }
+
+            for (action, keys) in mode.defaults() {
+                if overrides.contains_key(action) {
+                    continue;
+                }
+
+                for keys in keys.iter().filter_map(|k| parse_sequence(k).ok()) {
+                    if !resolved.iter().any(|b| b.keys == keys) {
+                        resolved.push(Binding { keys, action: *action });
+                    }
+                }
+            }
+
+            *bindings.mode_mut(mode) = resolved;
+        }
+
+        (bindings, errors)
+    }
+
+    fn mode_mut(&mut self, mode: Mode) -> &mut Vec<Binding> {
+        match mode {
+            Mode::Normal => &mut self.normal,
+            Mode::List => &mut self.list,
+            Mode::Calendar => &mut self.calendar,
+            Mode::Prompt => &mut self.prompt,
+        }
+    }
+
+    pub fn mode(&self, mode: Mode) -> &[Binding] {
+        match mode {
+            Mode::Normal => &self.normal,
+            Mode::List => &self.list,
+            Mode::Calendar => &self.calendar,
+            Mode::Prompt => &self.prompt,
+        }
+    }
+
+    pub fn lookup(&self, mode: Mode, pressed: &[KeyPress]) -> Lookup {
+        let bindings = self.mode(mode);
+
+        if let Some(binding) = bindings.iter().find(|b| b.keys == pressed) {
+            return Lookup::Action(binding.action);
+        }
+
+        if bindings.iter().any(|b| b.keys.starts_with(pressed)) {
+            return Lookup::Pending;
+        }
+
+        Lookup::None
+    }
+
+    /// Names of the keys bound to an action, in the order they were given
+    pub fn keys(&self, mode: Mode, action: Action) -> Vec<String> {
+        self.mode(mode)
+            .iter()
+            .filter(|b| b.action == action)
+            .map(|b| b.name())
+            .collect()
+    }
+
+    /// First key bound to an action, for the guide
+    pub fn key(&self, mode: Mode, action: Action) -> String {
+        self.keys(mode, action).into_iter().next().unwrap_or_default()
+    }
+}
+
+/// Reads keys separated by spaces, e.g. `g g`
+fn parse_sequence(keys: &str) -> anyhow::Result<Vec<KeyPress>> {
+    let keys: Vec<KeyPress> = keys.split_whitespace().map(KeyPress::parse).collect::<anyhow::Result<_>>()?;
+
+    if keys.is_empty() {
+        return Err(anyhow!("No key given"));
+    }
+
+    Ok(keys)
+}
@@ -1,12 +1,44 @@
+mod action;
+mod aggregate;
 mod app;
+mod bulk;
+mod cli;
+mod credentials;
+mod dates;
+mod editor;
+mod export;
+mod filter;
+mod group;
 mod github;
+mod history;
+mod import;
+mod insights;
+mod keymap;
+mod number;
 mod project;
+mod sort;
+mod text;
+mod theme;
 mod ui;
+mod view;
 
 fn main() {
-    let app = app::App::new();
+    // Has to be read while this is the only thread
+    let utc_offset = dates::local_offset();
 
-    let t = ui::start_app(app);
+    // A command runs headless instead of the editor, options alone such as
+    // --token-file are passed on to it
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let editor_args = match cli::Args::parse(&args) {
+        Ok(parsed) if parsed.positional.is_empty() && !parsed.help => parsed,
+        _ => std::process::exit(cli::run(&args)),
+    };
+
+    let mut app = app::App::new();
+    app.open_project = editor_args.option("project").map(String::from);
+    app.utc_offset = utc_offset;
+
+    let t = ui::start_app(app, editor_args.option("token-file"));
     
     // Make sure the user's terminal doesn't break if
     // an error happens
@@ -0,0 +1,121 @@
+//! Reading and stepping the values of number fields. GitHub stores numbers as
+//! floats, so signs, decimals and exponents are all allowed, but not the
+//! infinities and NaN that Rust would also parse.
+
+use anyhow::anyhow;
+
+/// Reads a number such as `-3`, `2.5`, `.5` or `1e3`
+pub fn parse(input: &str) -> anyhow::Result<f32> {
+    let input = input.trim();
+
+    if input.is_empty() {
+        return Err(anyhow!("Enter a number"));
+    }
+
+    if !is_number(input) {
+        return Err(anyhow!("\"{input}\" is not a number"));
+    }
+
+    let number: f32 = input
+        .parse()
+        .map_err(|_| anyhow!("\"{input}\" is not a number"))?;
+
+    if !number.is_finite() {
+        return Err(anyhow!("{input} is too large"));
+    }
+
+    Ok(number)
+}
+
+/// Whether a character can appear in a number, used to ignore other keys
+pub fn is_number_char(c: char) -> bool {
+    c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')
+}
+
+/// Adds `delta` to the number typed so far, keeping as many decimal places as
+/// it had. Empty input counts as zero.
+pub fn step(input: &str, delta: f32) -> anyhow::Result<String> {
+    let input = input.trim();
+    let number = if input.is_empty() { 0.0 } else { parse(input)? };
+
+    let stepped = number + delta;
+    if input.contains(['e', 'E']) {
+        return Ok(stepped.to_string());
+    }
+
+    let decimals = input.split_once('.').map_or(0, |(_, fraction)| fraction.len());
+    Ok(format!("{stepped:.decimals$}"))
+}
+
+/// Sign, digits with at most one decimal point, then an optional exponent
+fn is_number(input: &str) -> bool {
+    let input = input.strip_prefix(['-', '+']).unwrap_or(input);
+
+    let (mantissa, exponent) = match input.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (input, None),
+    };
+
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+
+    if whole.is_empty() && fraction.is_empty() || !digits(whole) || !digits(fraction) {
+        return false;
+    }
+
+    match exponent {
+        Some(exponent) => {
+            let exponent = exponent.strip_prefix(['-', '+']).unwrap_or(exponent);
+            !exponent.is_empty() && digits(exponent)
+        }
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_signs_decimals_and_exponents() {
+        assert_eq!(parse("42").unwrap(), 42.0);
+        assert_eq!(parse(" -3 ").unwrap(), -3.0);
+        assert_eq!(parse("+7").unwrap(), 7.0);
+        assert_eq!(parse("2.5").unwrap(), 2.5);
+        assert_eq!(parse(".5").unwrap(), 0.5);
+        assert_eq!(parse("5.").unwrap(), 5.0);
+        assert_eq!(parse("1e3").unwrap(), 1000.0);
+        assert_eq!(parse("-1.5E-2").unwrap(), -0.015);
+        assert_eq!(parse("2e+2").unwrap(), 200.0);
+    }
+
+    #[test]
+    fn rejects_everything_else() {
+        for input in ["", "  ", "-", "+", ".", "1.2.3", "--1", "+-1", "1e", "e3", "1e-", "1e2.5", "1,000", "12a", "0x10"] {
+            assert!(parse(input).is_err(), "{input:?} was accepted");
+        }
+    }
+
+    #[test]
+    fn rejects_what_rust_parses_but_github_does_not_store() {
+        for input in ["inf", "-infinity", "NaN", "1e39", "-1e39"] {
+            assert!(parse(input).is_err(), "{input:?} was accepted");
+        }
+    }
+
+    #[test]
+    fn number_chars() {
+        assert!("-+.eE0123456789".chars().all(is_number_char));
+        assert!(!"a ,x".chars().any(is_number_char));
+    }
+
+    #[test]
+    fn step_keeps_decimal_places() {
+        assert_eq!(step("", 1.0).unwrap(), "1");
+        assert_eq!(step("4", -1.0).unwrap(), "3");
+        assert_eq!(step("2.50", 1.0).unwrap(), "3.50");
+        assert_eq!(step("-0.5", 1.0).unwrap(), "0.5");
+        assert_eq!(step("1e2", 1.0).unwrap(), "101");
+        assert!(step("abc", 1.0).is_err());
+    }
+}
@@ -1,14 +1,15 @@
 use serde::{Deserialize, Serialize};
 use serde_json::*;
-use ratatui::style::{Style, Stylize};
+use ratatui::style::Style;
+use crate::theme::Theme;
 
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct User {
     pub login: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Item {
     pub id: String,
@@ -16,6 +17,59 @@ pub struct Item {
     pub field_values: Nodes<ProjectV2ItemField>,
     #[serde(rename(deserialize = "type"))]
     pub item_type: String,
+    #[serde(default)]
+    pub content: Option<Content>,
+    #[serde(default)]
+    pub is_archived: bool,
+}
+
+impl Item {
+    /// Title of the item, taken from the title field or the item content
+    pub fn title(&self) -> String {
+        match self.field_values.nodes.iter().find(|v| v.get_type() == "TITLE") {
+            Some(title) => title.value(),
+            None => match &self.content {
+                Some(Content::DraftIssue { title, .. })
+                | Some(Content::Issue { title, .. })
+                | Some(Content::PullRequest { title, .. }) => title.to_owned(),
+                _ => String::new(),
+            },
+        }
+    }
+
+    /// Logins of the users assigned to the issue or pull request
+    pub fn assignees(&self) -> Vec<String> {
+        match &self.content {
+            Some(Content::Issue { assignees, .. }) | Some(Content::PullRequest { assignees, .. }) => {
+                assignees.nodes.iter().map(|u| u.login.clone()).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Link to the issue or pull request, drafts have none
+    pub fn url(&self) -> Option<&str> {
+        match &self.content {
+            Some(Content::Issue { url, .. }) | Some(Content::PullRequest { url, .. }) => Some(url),
+            _ => None,
+        }
+    }
+
+    /// When the issue or pull request was closed, as an RFC 3339 timestamp
+    pub fn closed_at(&self) -> Option<&str> {
+        match &self.content {
+            Some(Content::Issue { closed_at, .. }) | Some(Content::PullRequest { closed_at, .. }) => closed_at.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Node ID of the issue or pull request behind the item, drafts have none
+    pub fn content_id(&self) -> Option<&str> {
+        match &self.content {
+            Some(Content::Issue { id, .. }) | Some(Content::PullRequest { id, .. }) => Some(id),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -64,21 +118,34 @@ impl ProjectV2ItemField {
         }
     }
 
-    pub fn style(&self) -> Style {
+    pub fn style(&self, theme: &Theme) -> Style {
         use ProjectV2ItemField::*;
         
         match self {
             TextValue { text: _, field: _ } => Style::default(),
-            DateValue { date: _, field: _ } => Style::default().bold(),
+            DateValue { date: _, field: _ } => theme.date,
             SingleSelectValue { name, field } => 
-                field.options.iter().find(|v| &v.name == name).unwrap().style() 
+                field.options.iter().find(|v| &v.name == name).unwrap().style(theme) 
             ,
-            NumberValue { number: _, field: _ } => Style::default().light_blue(),
-            IterationValue { duration: _, title: _, field: _ } => Style::default().bold(), 
+            NumberValue { number: _, field: _ } => theme.number,
+            IterationValue { duration: _, title: _, field: _ } => theme.iteration, 
             Empty(_) => Style::default(),
         }
     }
 
+    pub fn get_field_id(&self) -> &str {
+        use ProjectV2ItemField::*;
+
+        match self {
+            SingleSelectValue { name: _, field } => &field.id,
+            DateValue { date: _, field } |
+            NumberValue { number: _, field } |
+            TextValue { text: _, field } => field.get_id(),
+            IterationValue { duration: _, title: _, field } => &field.id,
+            Empty(_) => "",
+        }
+    }
+
     pub fn get_type(&self) -> &str {
         use ProjectV2ItemField::*;
 
@@ -131,14 +198,30 @@ impl Nodes<ProjectV2ItemField> {
                     TextValue { text, field: _ } => *text = s,
                     DateValue { date, field: _ } => *date = s,
                     SingleSelectValue { name, field: _ } => *name = s,
-                    NumberValue { number, field: _ } => *number = s.parse().unwrap(),
+                    // Numbers are checked before they get here, anything else is left as it was
+                    NumberValue { number, field: _ } => *number = s.parse().unwrap_or(*number),
                     IterationValue { duration: _, title, field: _ } => *title = s,
                 };
             }
         }
+
+    /// Removes the value for a field, leaving it empty
+    pub fn remove_value(&mut self, index: &str) {
+        use ProjectV2ItemField::*;
+
+        self.nodes.retain(|v|
+            match v {
+                Empty(_) => true,
+                TextValue { text: _, field } => field.get_name() != index,
+                DateValue { date: _, field } => field.get_name() != index,
+                SingleSelectValue { name: _, field } => field.name != index,
+                NumberValue { number: _, field } => field.get_name() != index,
+                IterationValue { duration: _, title: _, field } => field.name != index,
+            });
+    }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 #[serde(untagged)]
 pub enum Content {
     DraftIssue {
@@ -146,17 +229,25 @@ pub enum Content {
         body: String,
     },
     Issue {
+        id: String,
         title: String,
+        url: String,
         assignees: Nodes<User>,
+        #[serde(default, rename = "closedAt")]
+        closed_at: Option<String>,
     },
     PullRequest {
+        id: String,
         title: String,
+        url: String,
         assignees: Nodes<User>,
+        #[serde(default, rename = "closedAt")]
+        closed_at: Option<String>,
     },
     Empty(Value),
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct Nodes<T> {
     pub nodes: Vec<T>,
 }
@@ -167,10 +258,29 @@ impl Default for Nodes<ProjectV2ItemField> {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct Project {
     pub id: String,
     pub title: String,
+    /// User or organization the project belongs to
+    #[serde(default)]
+    pub owner: ProjectOwner,
+    /// Account the project was loaded with, set after fetching
+    #[serde(default)]
+    pub account: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ProjectOwner {
+    #[serde(default)]
+    pub login: String,
+}
+
+impl Project {
+    /// `Owner/Title`, as given to `--project`
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner.login, self.title)
+    }
 }
 
 
@@ -275,6 +385,9 @@ pub struct Iteration {
     pub start_date: String,
     pub id: String,
     pub title: String,
+    /// Length in days
+    #[serde(default)]
+    pub duration: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -291,19 +404,8 @@ pub struct FieldOption {
 }
 
 impl FieldOption {
-    pub fn style(&self) -> Style {
-        match self.color.as_str() {
-            "BLUE" => Style::default().blue(),
-            "GRAY" => Style::default().yellow(),
-            "GREEN" => Style::default().green(),
-            "ORANGE" => Style::default().light_red(),
-            "PINK" => Style::default().light_magenta(),
-            "PURPLE" => Style::default().magenta(),
-            "YELLOW" => Style::default().yellow(),
-
-            _ => Style::default(),
-        }
-
+    pub fn style(&self, theme: &Theme) -> Style {
+        theme.option(&self.color)
     }
 }
 
@@ -0,0 +1,69 @@
+use crate::project::*;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// A key the items in the table are ordered by
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sort {
+    pub field: String,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+/// Sorts by every key, ties on one key going to the next
+pub fn apply_all(keys: &[Sort], items: &mut [Item]) {
+    // Stable sorts from the last key to the first leave the first in charge
+    for key in keys.iter().rev() {
+        key.apply(items);
+    }
+}
+
+impl Sort {
+    /// Sorts by the field's value, with empty values last either way
+    pub fn apply(&self, items: &mut [Item]) {
+        items.sort_by(|a, b| self.compare(a, b));
+    }
+
+    pub fn compare(&self, a: &Item, b: &Item) -> Ordering {
+        let a = a.field_values.get_from_field(&self.field);
+        let b = b.field_values.get_from_field(&self.field);
+
+        match (is_empty(a), is_empty(b)) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) if self.descending => compare(a, b).reverse(),
+            (false, false) => compare(a, b),
+        }
+    }
+}
+
+fn is_empty(value: &ProjectV2ItemField) -> bool {
+    matches!(value, ProjectV2ItemField::Empty(_)) || value.value().is_empty()
+}
+
+fn compare(a: &ProjectV2ItemField, b: &ProjectV2ItemField) -> Ordering {
+    use ProjectV2ItemField::*;
+
+    match (a, b) {
+        (NumberValue { number: a, field: _ }, NumberValue { number: b, field: _ }) => {
+            a.partial_cmp(b).unwrap_or(Ordering::Equal)
+        }
+
+        // Options and iterations keep the order they have in the project
+        (SingleSelectValue { name: a, field }, SingleSelectValue { name: b, field: _ }) => {
+            let position = |name: &str| field.options.iter().position(|o| o.name == name);
+            position(a).cmp(&position(b))
+        }
+        (
+            IterationValue { title: a, field, .. },
+            IterationValue { title: b, field: _, .. },
+        ) => {
+            let position = |title: &str| field.configuration.iterations.iter().position(|i| i.title == title);
+            position(a).cmp(&position(b))
+        }
+
+        // Dates are YYYY-MM-DD, so they sort as text
+        _ => a.value().to_lowercase().cmp(&b.value().to_lowercase()),
+    }
+}
@@ -0,0 +1,179 @@
+//! Helpers for measuring and editing text by what is shown on screen. Cursors
+//! count grapheme clusters rather than bytes, so an emoji or a letter with a
+//! combining accent moves and deletes as one character.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Number of terminal cells the text takes up
+pub fn width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+pub fn grapheme_count(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+/// Byte offset of the grapheme at `cursor`, or the end of the text
+pub fn byte_index(text: &str, cursor: usize) -> usize {
+    text.grapheme_indices(true)
+        .nth(cursor)
+        .map_or(text.len(), |(i, _)| i)
+}
+
+/// Cells between the start of the text and the cursor
+pub fn cursor_width(text: &str, cursor: usize) -> usize {
+    width(&text[..byte_index(text, cursor)])
+}
+
+/// Inserts a character at the cursor, returning the new cursor position.
+/// A combining character joins the grapheme before it, so the cursor may not
+/// move.
+pub fn insert_char(text: &mut String, cursor: usize, c: char) -> usize {
+    let index = byte_index(text, cursor);
+    text.insert(index, c);
+    grapheme_count(&text[..index + c.len_utf8()])
+}
+
+/// Removes the grapheme before the cursor, returning the new cursor position
+pub fn remove_before(text: &mut String, cursor: usize) -> usize {
+    if cursor == 0 {
+        return 0;
+    }
+
+    let start = byte_index(text, cursor - 1);
+    let end = byte_index(text, cursor);
+    text.replace_range(start..end, "");
+    cursor - 1
+}
+
+/// Shortens text to fit in `max_width` cells, marking the cut with an ellipsis
+pub fn truncate(text: &str, max_width: usize) -> String {
+    if width(text) <= max_width {
+        return text.to_string();
+    }
+
+    let mut cut = String::new();
+    for grapheme in text.graphemes(true) {
+        if width(&cut) + width(grapheme) + 1 > max_width {
+            break;
+        }
+        cut.push_str(grapheme);
+    }
+
+    cut.push('…');
+    cut
+}
+
+/// Pads text with `fill` up to `total` cells
+pub fn pad(text: &str, total: usize, fill: char) -> String {
+    let mut padded = text.to_string();
+    padded.extend(std::iter::repeat_n(fill, total.saturating_sub(width(text))));
+    padded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAMILY: &str = "👩\u{200D}💻";
+    const THUMBS_UP: &str = "👍🏽";
+    const E_ACUTE: &str = "e\u{301}";
+
+    #[test]
+    fn width_counts_cells() {
+        assert_eq!(width("abc"), 3);
+        assert_eq!(width("日本語"), 6);
+        assert_eq!(width(E_ACUTE), 1);
+    }
+
+    #[test]
+    fn graphemes_count_as_one_character() {
+        assert_eq!(grapheme_count("日本"), 2);
+        assert_eq!(grapheme_count(FAMILY), 1);
+        assert_eq!(grapheme_count(THUMBS_UP), 1);
+        assert_eq!(grapheme_count(E_ACUTE), 1);
+        assert_eq!(grapheme_count(&format!("a{FAMILY}{E_ACUTE}b")), 4);
+    }
+
+    #[test]
+    fn cursor_moves_over_whole_graphemes() {
+        let text = format!("a{FAMILY}b");
+        assert_eq!(byte_index(&text, 1), 1);
+        assert_eq!(byte_index(&text, 2), 1 + FAMILY.len());
+        assert_eq!(byte_index(&text, 9), text.len());
+
+        assert_eq!(byte_index("日本", 1), 3);
+        assert_eq!(cursor_width("日本語", 2), 4);
+        assert_eq!(cursor_width(&format!("{E_ACUTE}x"), 1), 1);
+    }
+
+    #[test]
+    fn insert_between_wide_characters() {
+        let mut text = String::from("日本");
+        assert_eq!(insert_char(&mut text, 1, 'x'), 2);
+        assert_eq!(text, "日x本");
+    }
+
+    #[test]
+    fn combining_characters_join_the_grapheme_before() {
+        let mut text = String::from("e");
+        assert_eq!(insert_char(&mut text, 1, '\u{301}'), 1);
+        assert_eq!(text, E_ACUTE);
+
+        let mut text = String::from("👍");
+        assert_eq!(insert_char(&mut text, 1, '🏽'), 1);
+        assert_eq!(text, THUMBS_UP);
+
+        let mut text = String::from("👩");
+        let cursor = insert_char(&mut text, 1, '\u{200D}');
+        assert_eq!(insert_char(&mut text, cursor, '💻'), 1);
+        assert_eq!(text, FAMILY);
+    }
+
+    #[test]
+    fn remove_deletes_whole_graphemes() {
+        let mut text = format!("a{FAMILY}b");
+        assert_eq!(remove_before(&mut text, 2), 1);
+        assert_eq!(text, "ab");
+
+        let mut text = format!("{E_ACUTE}x");
+        assert_eq!(remove_before(&mut text, 1), 0);
+        assert_eq!(text, "x");
+
+        let mut text = format!("日{THUMBS_UP}");
+        assert_eq!(remove_before(&mut text, 2), 1);
+        assert_eq!(text, "日");
+
+        let mut text = String::from("日本");
+        assert_eq!(remove_before(&mut text, 0), 0);
+        assert_eq!(text, "日本");
+    }
+
+    #[test]
+    fn truncate_fits_wide_text() {
+        assert_eq!(truncate("日本語", 6), "日本語");
+        assert_eq!(truncate("日本語テキスト", 7), "日本語…");
+        assert_eq!(truncate("日本語テキスト", 8), "日本語…");
+        assert!(width(&truncate("日本語テキスト", 8)) <= 8);
+    }
+
+    #[test]
+    fn truncate_keeps_graphemes_whole() {
+        let text = E_ACUTE.repeat(3);
+        assert_eq!(truncate(&text, 2), format!("{E_ACUTE}…"));
+
+        let text = FAMILY.repeat(2);
+        assert_eq!(truncate(&text, width(&text) - 1), format!("{FAMILY}…"));
+
+        let text = THUMBS_UP.repeat(2);
+        assert_eq!(truncate(&text, width(&text) - 1), format!("{THUMBS_UP}…"));
+    }
+
+    #[test]
+    fn pad_counts_cells() {
+        assert_eq!(pad("日本", 6, '-'), "日本--");
+        assert_eq!(pad(E_ACUTE, 3, ' '), format!("{E_ACUTE}  "));
+        assert_eq!(pad("日本語", 4, '-'), "日本語");
+    }
+}
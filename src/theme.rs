@@ -0,0 +1,296 @@
+//! Colors and text styles of the editor. A preset is picked in the config and
+//! any of its styles can be changed, e.g.
+//!
+//! ```toml
+//! [theme]
+//! preset = "light"
+//!
+//! [theme.styles]
+//! cursor = { fg = "black", bg = "#ffd33d" }
+//! header = { bold = true }
+//!
+//! [theme.option_colors]
+//! RED = "#ff0000"
+//! ```
+
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Colors GitHub gives single select options
+pub const OPTION_COLORS: [&str; 8] = ["GRAY", "BLUE", "GREEN", "YELLOW", "ORANGE", "RED", "PINK", "PURPLE"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    #[default]
+    Dark,
+    Light,
+}
+
+/// The `[theme]` section of the config
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ThemeConfig {
+    #[serde(default)]
+    pub preset: Preset,
+    /// Use GitHub's exact colors, detected from `COLORTERM` when not set
+    #[serde(default)]
+    pub true_color: Option<bool>,
+    /// Changes to the preset's styles, by name
+    #[serde(default)]
+    pub styles: BTreeMap<String, StyleConfig>,
+    /// Colors for options, by GitHub color name
+    #[serde(default)]
+    pub option_colors: BTreeMap<String, String>,
+}
+
+/// Only the parts that are set replace the preset's
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct StyleConfig {
+    pub fg: Option<String>,
+    pub bg: Option<String>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underlined: Option<bool>,
+    pub reversed: Option<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Theme {
+    /// Project title
+    pub title: Style,
+    /// Secondary text such as the view summary and key hints
+    pub hint: Style,
+    /// Column headers
+    pub header: Style,
+    /// Header of the current column
+    pub header_current: Style,
+    /// Current cell
+    pub cursor: Style,
+    /// Marker in front of selected items
+    pub selection: Style,
+    /// Popup windows and prompts
+    pub popup: Style,
+    /// Header rows of groups
+    pub group: Style,
+    /// Column totals under the table
+    pub footer: Style,
+    /// Current entry of the project and account lists
+    pub popup_highlight: Style,
+    /// Key names in the guide and help
+    pub key: Style,
+    pub date: Style,
+    pub number: Style,
+    pub iteration: Style,
+    /// Picked day in the calendar
+    pub calendar_day: Style,
+    pub error: Style,
+    options: BTreeMap<String, Color>,
+}
+
+impl Theme {
+    /// Every style the config can change, by name
+    pub const STYLES: [&'static str; 16] = [
+        "title",
+        "hint",
+        "header",
+        "header_current",
+        "cursor",
+        "selection",
+        "popup",
+        "popup_highlight",
+        "group",
+        "footer",
+        "key",
+        "date",
+        "number",
+        "iteration",
+        "calendar_day",
+        "error",
+    ];
+
+    pub fn preset(preset: Preset, true_color: bool) -> Theme {
+        let accent = match preset {
+            Preset::Dark => Color::LightBlue,
+            Preset::Light => Color::Blue,
+        };
+        let muted = match preset {
+            Preset::Dark => Color::Gray,
+            Preset::Light => Color::DarkGray,
+        };
+
+        Theme {
+            title: Style::new().fg(Color::Green),
+            hint: Style::new().fg(muted),
+            header: Style::new(),
+            header_current: Style::new().fg(Color::Red),
+            cursor: Style::new().add_modifier(Modifier::REVERSED),
+            selection: Style::new().fg(accent),
+            popup: Style::new(),
+            popup_highlight: Style::new().fg(accent).add_modifier(Modifier::BOLD),
+            group: Style::new().fg(muted).add_modifier(Modifier::BOLD),
+            footer: Style::new().fg(muted),
+            key: Style::new().add_modifier(Modifier::BOLD),
+            date: Style::new().add_modifier(Modifier::BOLD),
+            number: Style::new().fg(accent),
+            iteration: Style::new().add_modifier(Modifier::BOLD),
+            calendar_day: Style::new().bg(Color::Red),
+            error: Style::new().fg(Color::Red),
+            options: OPTION_COLORS
+                .iter()
+                .map(|name| (name.to_string(), option_color(name, preset, true_color)))
+                .collect(),
+        }
+    }
+
+    /// The preset with the config's changes, and what couldn't be read
+    pub fn resolve(config: &ThemeConfig) -> (Theme, Vec<String>) {
+        let true_color = config.true_color.unwrap_or_else(supports_true_color);
+        let mut theme = Theme::preset(config.preset, true_color);
+        let mut errors = Vec::new();
+
+        for (name, changes) in &config.styles {
+            match theme.style_mut(name) {
+                Some(style) => match changes.apply(*style) {
+                    Ok(changed) => *style = changed,
+                    Err(err) => errors.push(format!("theme.styles.{name}: {err}")),
+                },
+                None => errors.push(format!(
+                    "theme.styles: no style named \"{name}\", they are {}",
+                    Theme::STYLES.join(", ")
+                )),
+            }
+        }
+
+        for (name, color) in &config.option_colors {
+            let name = name.to_uppercase();
+            if !OPTION_COLORS.contains(&name.as_str()) {
+                errors.push(format!("theme.option_colors: no GitHub color named \"{name}\""));
+                continue;
+            }
+
+            match parse_color(color) {
+                Ok(color) => {
+                    theme.options.insert(name, color);
+                }
+                Err(err) => errors.push(format!("theme.option_colors.{name}: {err}")),
+            }
+        }
+
+        (theme, errors)
+    }
+
+    /// Style of an option with the given GitHub color
+    pub fn option(&self, color: &str) -> Style {
+        match self.options.get(color) {
+            Some(color) => Style::new().fg(*color),
+            None => Style::new(),
+        }
+    }
+
+    fn style_mut(&mut self, name: &str) -> Option<&mut Style> {
+        Some(match name {
+            "title" => &mut self.title,
+            "hint" => &mut self.hint,
+            "header" => &mut self.header,
+            "header_current" => &mut self.header_current,
+            "cursor" => &mut self.cursor,
+            "selection" => &mut self.selection,
+            "popup" => &mut self.popup,
+            "popup_highlight" => &mut self.popup_highlight,
+            "group" => &mut self.group,
+            "footer" => &mut self.footer,
+            "key" => &mut self.key,
+            "date" => &mut self.date,
+            "number" => &mut self.number,
+            "iteration" => &mut self.iteration,
+            "calendar_day" => &mut self.calendar_day,
+            "error" => &mut self.error,
+            _ => return None,
+        })
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::resolve(&ThemeConfig::default()).0
+    }
+}
+
+impl StyleConfig {
+    fn apply(&self, mut style: Style) -> Result<Style, String> {
+        if let Some(fg) = &self.fg {
+            style.fg = Some(parse_color(fg)?);
+        }
+        if let Some(bg) = &self.bg {
+            style.bg = Some(parse_color(bg)?);
+        }
+
+        let modifiers = [
+            (self.bold, Modifier::BOLD),
+            (self.italic, Modifier::ITALIC),
+            (self.underlined, Modifier::UNDERLINED),
+            (self.reversed, Modifier::REVERSED),
+        ];
+        for (set, modifier) in modifiers {
+            style = match set {
+                Some(true) => style.add_modifier(modifier),
+                Some(false) => style.remove_modifier(modifier),
+                None => style,
+            };
+        }
+
+        Ok(style)
+    }
+}
+
+/// A color name like `light_blue`, a `#rrggbb` value or a palette index
+fn parse_color(color: &str) -> Result<Color, String> {
+    Color::from_str(color).map_err(|_| format!("\"{color}\" isn't a color"))
+}
+
+/// Terminals that can show any RGB color say so in `COLORTERM`
+fn supports_true_color() -> bool {
+    std::env::var("COLORTERM").is_ok_and(|v| v == "truecolor" || v == "24bit")
+}
+
+/// GitHub's own option colors from its light and dark themes, or the nearest
+/// of the 16 terminal colors
+fn option_color(name: &str, preset: Preset, true_color: bool) -> Color {
+    if true_color {
+        let (dark, light) = match name {
+            "GRAY" => (0x9198a1, 0x59636e),
+            "BLUE" => (0x4493f8, 0x0969da),
+            "GREEN" => (0x3fb950, 0x1a7f37),
+            "YELLOW" => (0xd29922, 0x9a6700),
+            "ORANGE" => (0xdb6d28, 0xbc4c00),
+            "RED" => (0xf85149, 0xd1242f),
+            "PINK" => (0xdb61a2, 0xbf3989),
+            "PURPLE" => (0xab7df8, 0x8250df),
+            _ => return Color::Reset,
+        };
+
+        return match preset {
+            Preset::Dark => rgb(dark),
+            Preset::Light => rgb(light),
+        };
+    }
+
+    match name {
+        "GRAY" if preset == Preset::Light => Color::DarkGray,
+        "GRAY" => Color::Gray,
+        "BLUE" => Color::Blue,
+        "GREEN" => Color::Green,
+        "YELLOW" => Color::Yellow,
+        "ORANGE" => Color::LightRed,
+        "RED" => Color::Red,
+        "PINK" => Color::LightMagenta,
+        "PURPLE" => Color::Magenta,
+        _ => Color::Reset,
+    }
+}
+
+fn rgb(hex: u32) -> Color {
+    Color::Rgb((hex >> 16) as u8, (hex >> 8) as u8, hex as u8)
+}
@@ -1,15 +1,26 @@
-use crate::app::{self, add_item_keys};
+use crate::action::{self, Action};
+use crate::aggregate;
+use crate::app::{self, add_item_keys, bulk_keys, export_keys, message_keys, mouse_events, ClickAreas};
+use crate::app::{confirm_keys, filter_keys, help_keys, insights_keys, palette_keys, save_view_keys};
 use crate::app::{
-    insert_mode_keys, normal_mode_keys, switch_project_keys, App, FieldBuffer, InputMode,
+    insert_mode_keys, normal_mode_keys, switch_account_keys, switch_project_keys, Account, App, Config, FieldBuffer,
+    InputMode,
 };
-use crate::github;
-use crate::project::{Field, Item, ProjectV2ItemField};
-use std::rc::Rc;
+use crate::editor::LineEditor;
+use crate::credentials::{self, Locked, Login, Saved, TokenSource};
+use crate::dates;
+use anyhow::anyhow;
+use crate::github::{self, Auth};
+use crate::group::{self, TableRow};
+use crate::insights;
+use crate::keymap::{Bindings, Mode};
+use crate::project::{Field, Item, Project, ProjectV2ItemField};
+use crate::text;
 use std::sync::mpsc;
 use std::thread;
 
 use crossterm::{
-    event::{self, KeyEventKind},
+    event::{self, DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEventKind},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
@@ -18,97 +29,118 @@ use ratatui::widgets::calendar::CalendarEventStore;
 use ratatui::{prelude::*, widgets::*};
 use std::io::stdout;
 use std::result::Result;
-use std::{cmp, fs, vec};
+use std::{cmp, vec};
 use time::Instant;
 
 type CTerminal = Terminal<CrosstermBackend<std::io::Stdout>>;
 
+/// OAuth app used to log in through the browser
+const CLIENT_ID: &str = include_str!("client_id");
+const SCOPE: &str = "project,user";
+
+/// Narrowest a column gets when resized or fitted to the terminal
+pub const MIN_COLUMN_WIDTH: u16 = 4;
+
 pub fn disable_terminal() -> anyhow::Result<()> {
+    stdout().execute(DisableMouseCapture)?;
     stdout().execute(LeaveAlternateScreen)?;
     disable_raw_mode()?;
 
     Ok(())
 }
 
-pub fn start_app(mut app: App) -> anyhow::Result<()> {
+pub fn start_app(mut app: App, token_file: Option<&str>) -> anyhow::Result<()> {
     stdout().execute(EnterAlternateScreen)?;
+    stdout().execute(EnableMouseCapture)?;
     enable_raw_mode()?;
 
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
     terminal.clear()?;
 
-    // Auth and load info
-    let cred = draw_auth(&mut terminal)?;
+    // Logs in to the current account, coming back here whenever another
+    // one is picked
+    loop {
+        let account = app.config.account();
+        if !app.logins.contains_key(&account.name) {
+            let login = match app.external_login(&account, token_file)? {
+                Some(login) => login,
+                None => login(&mut terminal, &mut app, &account)?,
+            };
+            app.logins.insert(account.name.clone(), login);
+        }
+        check_login(&mut terminal, &mut app, &account)?;
 
-    let _ = std::fs::write(
-        "./access_token",
-        serde_json::to_string(&cred).expect("Failed to serialize"),
-    );
+        if app.config.merge_accounts {
+            app.add_saved_logins(token_file);
+        }
 
-    app.id = Some(cred);
-    app.reload_info()?;
+        app.relogin = false;
+        app.reload_info()?;
+        if let InputMode::LoadingProject = app.menu_state {
+            app.menu_state = InputMode::Normal;
+        }
 
-    // Actual UI once loaded
-    draw_projects_editor(app, &mut terminal)?;
+        // Actual UI once loaded
+        app = draw_projects_editor(app, &mut terminal)?;
 
-    Ok(())
+        if !app.relogin {
+            return Ok(());
+        }
+    }
 }
 
 pub fn app_updater(
-    token: String,
-    project_state: usize,
-    tx: mpsc::Sender<(app::UserInfo, usize)>,
+    auths: Vec<(String, Auth)>,
+    account: String,
+    project: Project,
+    tx: mpsc::Sender<(app::UserInfo, String)>,
 ) -> anyhow::Result<()> {
-    let user = github::get_user(&token)?;
-    let projects = github::get_project_ids(&token, &user.login)?;
-    let items = github::fetch_project_items(&token, &projects[project_state].id)?;
-    let fields = github::fetch_project_fields(&token, &projects[project_state].id)?;
-
-    Ok(tx.send((
-        app::UserInfo {
-            user,
-            projects,
-            items,
-            fields,
-        },
-        project_state,
-    ))?)
+    let (user, mut projects) = app::fetch_projects(&auths, &account)?;
+
+    // Projects opened by ID or name aren't in the account's list
+    let index = match projects.iter().position(|p| p.id == project.id) {
+        Some(index) => index,
+        None => {
+            projects.push(project.clone());
+            projects.len() - 1
+        }
+    };
+    let info = app::fetch_project(&auths, user, projects, index)?;
+
+    Ok(tx.send((info, project.id))?)
 }
 
-pub(crate) fn draw_projects_editor(mut app: App, terminal: &mut CTerminal) -> anyhow::Result<()> {
-    let mut n_widths = get_widths(&app, &app.info()?.fields, &app.info()?.items);
-    let mut widths = constrained_widths(&n_widths);
-    let mut headers = get_headers(&app.info()?.fields, &n_widths);
-    let mut offset = 0;
+/// Runs the editor until it is quit or another account is picked
+pub(crate) fn draw_projects_editor(mut app: App, terminal: &mut CTerminal) -> anyhow::Result<App> {
+    let mut columns: Vec<usize> = Vec::new();
     let mut last_refresh = Instant::now();
 
-    let (tx, rx) = mpsc::channel::<(app::UserInfo, usize)>();
+    let (tx, rx) = mpsc::channel::<(app::UserInfo, String)>();
 
     loop {
         match rx.try_recv() {
-            Ok((u, p)) => {
-                if p != app.config.project_state {
-                    return Ok(());
-                }
+            // Refreshes started before switching project are dropped
+            Ok((u, id)) if app.project_id().is_ok_and(|open| open == id) => {
                 if let InputMode::LoadingProject = app.menu_state {
                     app.menu_state = InputMode::Normal;
                 }
 
-                app.user_info = Some(u);
-
-                n_widths = get_widths(&app, &app.info()?.fields, &app.info()?.items);
-                widths = constrained_widths(&n_widths);
-                headers = get_headers(&app.info()?.fields, &n_widths);
+                // Projects may have been added or deleted since
+                if let Some(index) = u.projects.iter().position(|p| p.id == id) {
+                    app.config.project_state = index;
+                }
+                app.set_info(u);
             }
-            Err(_) => {}
+            _ => {}
         };
 
-        if last_refresh.elapsed().whole_seconds() > 10 || app.reload {
-            let token = app.id.as_ref().unwrap().token.clone();
-            let proj_id = app.config.project_state;
+        let open = app.info().ok().and_then(|i| i.projects.get(app.config.project_state)).cloned();
+        if let Some(project) = open.filter(|_| last_refresh.elapsed().whole_seconds() > 10 || app.reload) {
+            let auths = app.auths();
+            let account = app.config.account().name;
             let tx_clone = tx.clone();
 
-            thread::spawn(move || -> anyhow::Result<()> { app_updater(token, proj_id, tx_clone) });
+            thread::spawn(move || -> anyhow::Result<()> { app_updater(auths, account, project, tx_clone) });
 
             last_refresh = Instant::now();
             app.reload = false;
@@ -116,11 +148,15 @@ pub(crate) fn draw_projects_editor(mut app: App, terminal: &mut CTerminal) -> an
 
         // Draw app
         terminal.draw(|frame| {
+            // Views get a row of tabs under the title
+            let views = app.views();
+            let title_height = if views.is_empty() { 2 } else { 3 };
+
             // Split frame into a title section, main and info section
             let layout = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints(vec![
-                    Constraint::Length(2),
+                    Constraint::Length(title_height),
                     Constraint::Min(5),
                     Constraint::Length(2),
                 ])
@@ -131,24 +167,82 @@ pub(crate) fn draw_projects_editor(mut app: App, terminal: &mut CTerminal) -> an
                 .borders(Borders::LEFT | Borders::RIGHT | Borders::TOP)
                 .style(Style::default());
 
-            let title = Paragraph::new(Text::styled(
-                app.info().unwrap().projects[1].title.clone(),
-                Style::default().fg(Color::Green),
-            ))
+            let title = Paragraph::new(Line::from(vec![
+                Span::styled(project_title(&app), app.theme.title),
+                Span::styled(view_summary(&app), app.theme.hint),
+            ]))
             .block(title_block);
 
             frame.render_widget(title, layout[0]);
 
-            // Find how many fields can be hidden to the left to fit the current
-            // on screen. The -10 can be changed for more comfort, or removed to
-            // avoid breakages
-            offset = find_minimum_offset(&n_widths, app.field_state, layout[1].width - 10);
+            if !views.is_empty() {
+                let selected = views.iter().position(|v| Some(&v.name) == app.view.as_ref()).map_or(0, |i| i + 1);
+                let labels: Vec<String> = std::iter::once(String::from("All items"))
+                    .chain(views.iter().map(|v| v.label()))
+                    .collect();
+
+                let row = Rect::new(layout[0].x + 1, layout[0].y + 2, layout[0].width.saturating_sub(2), 1);
+                frame.render_widget(
+                    Tabs::new(labels)
+                        .select(selected)
+                        .style(app.theme.hint)
+                        .highlight_style(app.theme.header_current),
+                    row,
+                );
+            }
+
+            // Account and where its token came from, on the right of the title
+            let account = app.config.account().name;
+            if let Some(login) = app.logins.get(&account) {
+                let status = match app.config.accounts.len() > 1 {
+                    true => format!("{account} · token: {} ", login.source),
+                    false => format!("token: {} ", login.source),
+                };
+                let status = Paragraph::new(status).alignment(Alignment::Right).style(app.theme.hint);
+                let row = Rect::new(layout[0].x + 1, layout[0].y + 1, layout[0].width.saturating_sub(2), 1);
+                frame.render_widget(status, row);
+            }
+
+            // Widths are worked out every frame so resizing and typing show
+            // up straight away
+            let mut n_widths = get_widths(&app, &app.info().unwrap().fields, &app.info().unwrap().items, layout[1].width - 10);
+            let headers = get_headers(&app.info().unwrap().fields, &n_widths);
+            app.drawn_widths = n_widths.clone();
+
+            // Hidden fields take no space and are left out below
+            for (i, width) in n_widths.iter_mut().enumerate() {
+                if app.is_field_hidden(i) {
+                    *width = 0;
+                }
+            }
+
+            // Find which fields fit on screen with the current one visible.
+            // The -10 can be changed for more comfort, or removed to avoid
+            // breakages
+            columns = visible_columns(
+                &n_widths,
+                app.field_state,
+                app.config.frozen_columns,
+                layout[1].width - 10,
+            );
+            columns.retain(|c| n_widths[*c] != 0);
+            let current_column = columns.iter().position(|c| *c == app.field_state).unwrap_or(0);
+
+            // The footer takes the last row of the table when there is room
+            let mut table_area = layout[1].inner(&Margin::new(1, 1));
+            let footer_area = match app.config.footer && table_area.height > 2 {
+                true => {
+                    table_area.height -= 1;
+                    Some(Rect::new(table_area.x, table_area.bottom(), table_area.width, 1))
+                }
+                false => None,
+            };
 
             // Layout for Lists
             let lists_layout = Layout::default()
                 .direction(Direction::Horizontal)
-                .constraints(split_shift(&widths, offset))
-                .split(layout[1].inner(&Margin::new(1, 1)));
+                .constraints(column_constraints(&n_widths, &columns))
+                .split(table_area);
 
             // Draw List Border
             let border_set = symbols::border::Set {
@@ -166,84 +260,386 @@ pub(crate) fn draw_projects_editor(mut app: App, terminal: &mut CTerminal) -> an
 
             // Tabs Drawing
             frame.render_widget(
-                Tabs::new(headers[offset..].to_owned())
+                Tabs::new(columns.iter().map(|c| headers[*c].clone()).collect())
                     .padding("", "")
-                    .select(app.field_state - offset)
-                    .highlight_style(Style::new().red())
+                    .select(current_column)
+                    .style(app.theme.header)
+                    .highlight_style(app.theme.header_current)
                     .divider("|"),
                 scrolled.inner(&Margin::new(1, 0)),
             );
 
-            // TODO: custom index list
-            let list_state = ListState::default().with_selected(Some(app.item_state.clone()));
+            // All columns share one viewport so rows stay lined up
+            app.update_scroll(lists_layout[0].height as usize);
+            let list_state = ListState::default()
+                .with_selected(Some(app.cursor_line()))
+                .with_offset(app.scroll);
+            let rows = app.rows();
 
-            for i in offset..app.info().unwrap().fields.len() {
+            for (column, &i) in columns.iter().enumerate() {
+                // Group names go in the first column shown
                 frame.render_stateful_widget(
-                    draw_list(&app.info().unwrap().items, &app.info().unwrap().fields, i)
+                    draw_list(&app, &rows, i, column == 0, n_widths[i])
                         .highlight_style(if i == app.field_state {
-                            Style::default().reversed()
+                            app.theme.cursor
                         } else {
                             Style::not_reversed(Default::default())
                         }),
-                    lists_layout[i - offset],
+                    lists_layout[column],
                     &mut list_state.clone(),
                 );
             }
 
+            // Column totals, lined up with the columns above
+            if let Some(area) = footer_area {
+                let footer_layout = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints(column_constraints(&n_widths, &columns))
+                    .split(area);
+                let items = app.footer_items();
+
+                for (column, &i) in columns.iter().enumerate() {
+                    let summary = aggregate::summary(&app.config, &app.info().unwrap().fields[i], &items);
+                    frame.render_widget(
+                        Paragraph::new(text::truncate(&summary, (n_widths[i] as usize).saturating_sub(1)))
+                            .style(app.theme.footer),
+                        footer_layout[column],
+                    );
+                }
+            }
+
+            // Selection markers, drawn in the same column as the cursor
+            let lists_top = lists_layout[0].y;
+            for row in 0..app.viewport_height {
+                if let Some(TableRow::Item(item)) = rows.get(app.scroll + row) {
+                    if !app.is_selected(*item) {
+                        continue;
+                    }
+                    frame.render_widget(
+                        Paragraph::new("*").style(app.theme.selection),
+                        Rect::new(0, lists_top + row as u16, 1, 1),
+                    );
+                }
+            }
+
             // Side cursor, helps show which item is being edited.
-            let cursor_pos = layout[1].height.min(app.item_state as u16 + 3);
+            let cursor_pos = lists_top + app.cursor_row();
             frame.render_widget(Paragraph::new(">"), Rect::new(0, cursor_pos, 1, 1));
 
+            // Remembered so mouse clicks can be matched to cells
+            app.click_areas = ClickAreas {
+                columns: columns.iter().enumerate().map(|(column, &i)| (lists_layout[column], i)).collect(),
+                header_row: layout[1].y,
+                popup: None,
+                popup_offset: 0,
+            };
+
             // Extra drawing
+            let popup = app.theme.popup;
             match app.menu_state {
-                InputMode::Input => draw_editor(frame, &app, &lists_layout, offset).unwrap(),
-                InputMode::SwitchProject(p) => draw_project_list(&mut app, frame, p).unwrap(),
-                InputMode::LoadingProject => draw_info_window("Loading Project", layout[1], frame),
-                InputMode::Error(ref err) => draw_info_window(&format!("{err}\n\nHit Esc to close."), layout[1], frame),
-                InputMode::AddItem(ref s, _) => draw_info_window(&format!("Add Item: {s}"), layout[1], frame),
+                InputMode::Input => {
+                    let popup = draw_editor(frame, &app, lists_layout[current_column]).unwrap();
+                    app.click_areas.popup = popup.map(|(area, _)| area);
+                    app.click_areas.popup_offset = popup.map_or(0, |(_, offset)| offset);
+                }
+                InputMode::SwitchProject(_) => draw_project_list(&app, frame).unwrap(),
+                InputMode::SwitchAccount(index) => draw_account_list(&app, frame, index),
+                InputMode::LoadingProject => draw_info_window("Loading Project", popup, layout[1], frame),
+                InputMode::Error(ref err) => draw_info_window(&format!("{err}\n\nHit Esc to close."), popup, layout[1], frame),
+                InputMode::Message(ref msg) => draw_info_window(&format!("{msg}\n\nHit Esc to close."), popup, layout[1], frame),
+                InputMode::Confirm(action) => draw_info_window(&app.confirm_message(action), popup, layout[1], frame),
+                InputMode::AddItem(ref editor) => draw_prompt("Add Item: ", editor, popup, layout[1], frame),
+                InputMode::Export(ref editor) => draw_prompt("Export to: ", editor, popup, layout[1], frame),
+                InputMode::Filter(ref editor) => draw_prompt("Filter: ", editor, popup, layout[1], frame),
+                InputMode::SaveView(ref editor) => draw_prompt("Save view as: ", editor, popup, layout[1], frame),
+                InputMode::Palette(ref query, index) => draw_palette(&app, query, index, layout[1], frame),
+                InputMode::Help(scroll) => draw_help(&app, scroll, layout[1], frame),
+                InputMode::Insights(burnup) => draw_insights(&app, burnup, layout[1], frame),
 
                 _ => {}
             };
 
+            if let Some(job) = &app.bulk {
+                draw_info_window(
+                    &format!("{} item {} of {}\n\nHit Esc to stop.", job.action.verb(), job.done() + 1, job.total),
+                    popup,
+                    layout[1],
+                    frame,
+                );
+            }
+
             frame.render_widget(guide(&app), layout[2]);
         })?;
 
+        // Bulk actions run one item per frame so progress stays visible
+        if app.bulk.is_some() {
+            app.bulk_step();
+        }
+
         // Event/key management
         if event::poll(std::time::Duration::from_millis(16))? {
-            if let event::Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
+            match event::read()? {
+                event::Event::Key(key) if key.kind == KeyEventKind::Press => {
                     match &app.menu_state {
+                        _ if app.bulk.is_some() => bulk_keys(key, &mut app)?,
                         InputMode::Normal => normal_mode_keys(key, &mut app)?,
                         InputMode::SwitchProject(_) => switch_project_keys(key, &mut app)?,
-                        InputMode::AddItem(_, _) => add_item_keys(key, &mut app)?,
+                        InputMode::SwitchAccount(_) => switch_account_keys(key, &mut app)?,
+                        InputMode::AddItem(_) => add_item_keys(key, &mut app)?,
+                        InputMode::Export(_) => export_keys(key, &mut app)?,
+                        InputMode::Filter(_) => filter_keys(key, &mut app)?,
+                        InputMode::SaveView(_) => save_view_keys(key, &mut app)?,
+                        InputMode::Palette(_, _) => palette_keys(key, &mut app)?,
+                        InputMode::Help(_) => help_keys(key, &mut app)?,
+                        InputMode::Insights(_) => insights_keys(key, &mut app)?,
+                        InputMode::Confirm(_) => confirm_keys(key, &mut app)?,
+                        InputMode::Error(_) | InputMode::Message(_) => message_keys(key, &mut app)?,
                         _ => insert_mode_keys(key, &mut app)?,
                     };
                 }
+
+                event::Event::Mouse(mouse) if app.bulk.is_none() => mouse_events(mouse, &mut app)?,
+
+                _ => {}
+            }
+        }
+
+        if app.exit || app.relogin {
+            return Ok(app);
+        }
+
+        std::mem::replace(&mut app.error_hook, Ok(()))?;
+    }
+}
+
+/// The account's saved login, or a new one through the browser
+fn login(terminal: &mut CTerminal, app: &mut App, account: &Account) -> anyhow::Result<Login> {
+    let (credential, source) = match credentials::load(&account.name, app.config.encrypt_credentials)? {
+        Some(Saved::Locked(locked)) => (unlock(terminal, &locked, app.theme.popup)?, TokenSource::Saved),
+        Some(Saved::Plain(cred)) => {
+            // Saved before encryption was turned on
+            if app.config.encrypt_credentials {
+                if let Some(passphrase) = new_passphrase(terminal, app.theme.popup)? {
+                    save_login(app, &account.name, &cred, Some(&passphrase));
+                }
             }
+            (cred, TokenSource::Saved)
         }
+        None => (browser_login(terminal, app, account)?, TokenSource::DeviceFlow),
+    };
+
+    Ok(Login { credential, source })
+}
 
-        if app.exit {
+/// Logs in through the browser and saves the new login
+fn browser_login(terminal: &mut CTerminal, app: &mut App, account: &Account) -> anyhow::Result<Credential> {
+    let cred = draw_auth(terminal, &account.host)?;
+    store_login(terminal, app, &account.name, &cred)?;
+
+    Ok(cred)
+}
+
+/// Saves a new login, left unsaved when encrypting and no passphrase is given
+fn store_login(terminal: &mut CTerminal, app: &mut App, account: &str, cred: &Credential) -> anyhow::Result<()> {
+    if !app.config.encrypt_credentials {
+        save_login(app, account, cred, None);
+    } else if let Some(passphrase) = new_passphrase(terminal, app.theme.popup)? {
+        save_login(app, account, cred, Some(&passphrase));
+    }
+
+    Ok(())
+}
+
+/// Makes sure the account's token can still be used. An expired browser
+/// login is refreshed, and any other problem is explained with the choice to
+/// log in again. Network errors are left for loading the project to report.
+fn check_login(terminal: &mut CTerminal, app: &mut App, account: &Account) -> anyhow::Result<()> {
+    loop {
+        let Some(login) = app.logins.get(&account.name).cloned() else {
+            return Ok(());
+        };
+
+        let problem = if credentials::is_expired(&login.credential) {
+            let refreshed = match login.credential.refresh_token.is_empty() {
+                true => None,
+                false => github_device_flow::refresh(
+                    CLIENT_ID,
+                    &login.credential.refresh_token,
+                    Some(&account.host),
+                    Some(SCOPE),
+                )
+                .ok(),
+            };
+
+            match refreshed {
+                Some(cred) => {
+                    store_login(terminal, app, &account.name, &cred)?;
+                    app.logins.insert(
+                        account.name.clone(),
+                        Login {
+                            credential: cred,
+                            source: login.source,
+                        },
+                    );
+                    continue;
+                }
+                None => Some(github::TokenProblem::Expired),
+            }
+        } else {
+            github::check_token(&app.auth_for(&account.name)?).ok().flatten()
+        };
+
+        let Some(problem) = problem else {
             return Ok(());
+        };
+
+        if !draw_reauth(terminal, &problem, &login.source, app.theme.popup)? {
+            return Err(anyhow!("{problem}"));
+        }
+
+        let credential = browser_login(terminal, app, account)?;
+        app.logins.insert(
+            account.name.clone(),
+            Login {
+                credential,
+                source: TokenSource::DeviceFlow,
+            },
+        );
+    }
+}
+
+/// Explains why the token can't be used, returning whether to log in again
+/// through the browser
+fn draw_reauth(
+    terminal: &mut CTerminal,
+    problem: &github::TokenProblem,
+    source: &TokenSource,
+    style: Style,
+) -> anyhow::Result<bool> {
+    let tip = match source {
+        TokenSource::TokenFile(path) => format!("Or put a new token in {}", path.display()),
+        TokenSource::Env(var) => format!("Or set {var} to a new token"),
+        TokenSource::GhCli => String::from("Or run `gh auth refresh -s project`"),
+        TokenSource::Saved | TokenSource::DeviceFlow => String::from("Or run with `logout` to forget it"),
+    };
+    let text = format!("{problem} ({source})\n{tip}\n\nEnter to log in through the browser, Esc to quit");
+
+    loop {
+        terminal.draw(|frame| draw_info_window(&text, style, frame.size(), frame))?;
+
+        if let event::Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Enter => return Ok(true),
+                KeyCode::Esc => return Ok(false),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Saves the login, showing an error instead of stopping if that fails
+fn save_login(app: &mut App, account: &str, cred: &Credential, passphrase: Option<&str>) {
+    if let Err(err) = credentials::save(account, cred, passphrase) {
+        app.menu_state = InputMode::Error(format!("Couldn't save the login: {err}"));
+    }
+}
+
+/// Unlocks the saved login with the passphrase from the environment, or
+/// asks for it until it is right
+fn unlock(terminal: &mut CTerminal, locked: &Locked, style: Style) -> anyhow::Result<Credential> {
+    let mut error = None;
+
+    if let Some(passphrase) = credentials::env_passphrase() {
+        match locked.unlock(&passphrase) {
+            Ok(cred) => return Ok(cred),
+            Err(err) => error = Some(format!("{} in {}", err, credentials::PASSPHRASE_VAR)),
         }
+    }
+
+    loop {
+        let passphrase = draw_passphrase(terminal, "Passphrase: ", error.as_deref(), style)?
+            .ok_or_else(|| anyhow!("No passphrase given, run with `logout` to log in again"))?;
 
-        if let Err(err) = app.error_hook {
-            return Err(err);
+        match locked.unlock(&passphrase) {
+            Ok(cred) => return Ok(cred),
+            Err(err) => error = Some(err.to_string()),
         }
     }
 }
 
-pub fn draw_auth(terminal: &mut CTerminal) -> Result<Credential, DeviceFlowError> {
-    if let Ok(content) = fs::read_to_string("./access_token") {
-        if let Ok(cred) = serde_json::from_str(&content) {
-            return Ok(cred);
+/// Passphrase to encrypt a new login with, `None` if it shouldn't be saved
+fn new_passphrase(terminal: &mut CTerminal, style: Style) -> anyhow::Result<Option<String>> {
+    if let Some(passphrase) = credentials::env_passphrase() {
+        return Ok(Some(passphrase));
+    }
+
+    let mut error = None;
+
+    loop {
+        let Some(passphrase) = draw_passphrase(terminal, "New passphrase: ", error, style)? else {
+            return Ok(None);
+        };
+        let Some(repeated) = draw_passphrase(terminal, "Repeat passphrase: ", None, style)? else {
+            return Ok(None);
+        };
+
+        if passphrase == repeated {
+            return Ok(Some(passphrase));
         }
+        error = Some("The passphrases didn't match");
     }
+}
+
+/// Reads a passphrase without showing it, `None` when Esc is hit
+fn draw_passphrase(
+    terminal: &mut CTerminal,
+    label: &str,
+    error: Option<&str>,
+    style: Style,
+) -> anyhow::Result<Option<String>> {
+    let mut editor = LineEditor::new("");
+    let mut kill = String::new();
+
+    loop {
+        terminal.draw(|frame| {
+            let mut text = format!("{label}{}", "*".repeat(editor.width()));
+            if let Some(error) = error {
+                text.push_str(&format!("\n{error}"));
+            }
+            text.push_str("\n\nEnter to confirm, Esc to cancel");
+            draw_info_window(&text, style, frame.size(), frame);
+
+            // Matches where draw_info_window puts its text
+            let width = text.lines().map(text::width).max().unwrap_or(0);
+            let area = centered_rect(width as u16 + 4, text.lines().count() as u16 + 2, frame.size());
+            frame.set_cursor(
+                area.x + 1 + (text::width(label) + editor.cursor_width()) as u16,
+                area.y + 1,
+            );
+        })?;
 
-    let client_id = include_str!("client_id");
-    let scope = Some("project,user");
-    let host = Some("github.com");
+        if let event::Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
 
-    let mut flow = DeviceFlow::start(client_id, host, scope)?;
+            match key.code {
+                KeyCode::Enter if !editor.is_empty() => return Ok(Some(editor.text().to_string())),
+                KeyCode::Esc => return Ok(None),
+                _ => {
+                    editor.handle_key(key, &mut kill);
+                }
+            }
+        }
+    }
+}
+
+pub fn draw_auth(terminal: &mut CTerminal, host: &str) -> Result<Credential, DeviceFlowError> {
+    let mut flow = DeviceFlow::start(CLIENT_ID, Some(host), Some(SCOPE))?;
 
     terminal
         .draw(|frame| {
@@ -257,7 +653,7 @@ pub fn draw_auth(terminal: &mut CTerminal) -> Result<Credential, DeviceFlowError
                 .split(frame.size());
 
             let text = format!(
-                "Please visit https://github.com/login/device \nAnd paste in the code {}",
+                "Please visit https://{host}/login/device \nAnd paste in the code {}",
                 flow.user_code.as_ref().unwrap()
             );
 
@@ -273,17 +669,36 @@ pub fn draw_auth(terminal: &mut CTerminal) -> Result<Credential, DeviceFlowError
     flow.poll(20)
 }
 
-pub fn draw_project_list(app: &App, frame: &mut Frame, index: usize) -> anyhow::Result<()> {
+/// Title of the open project, with its owner when that isn't the user
+fn project_title(app: &App) -> String {
+    let Ok(info) = app.info() else {
+        return String::new();
+    };
+
+    match info.projects.get(app.config.project_state) {
+        Some(project) if project.owner.login.is_empty() || project.owner.login == info.user.login => {
+            project.title.clone()
+        }
+        Some(project) => project.full_name(),
+        None => String::new(),
+    }
+}
+
+pub fn draw_project_list(app: &App, frame: &mut Frame) -> anyhow::Result<()> {
     if let Some(app_info) = &app.user_info {
         if let InputMode::SwitchProject(index) = &app.menu_state {
-            let width = app_info
+            // Merged projects are labelled with their account
+            let labels: Vec<String> = app_info
                 .projects
                 .iter()
-                .fold(14, |max, accum| cmp::max(max, accum.title.len()));
-            let text = app_info
-                .projects
-                .iter()
-                .map(|p| ListItem::new(p.title.clone()));
+                .map(|p| match app.config.merge_accounts {
+                    true => format!("{} ({})", p.title, p.account),
+                    false => p.title.clone(),
+                })
+                .collect();
+
+            let width = labels.iter().fold(14, |max, accum| cmp::max(max, text::width(accum)));
+            let text = labels.into_iter().map(ListItem::new);
 
             let area = centered_rect(
                 width as u16 + 2,
@@ -293,11 +708,12 @@ pub fn draw_project_list(app: &App, frame: &mut Frame, index: usize) -> anyhow::
             let popup_block = Block::default()
                 .title("Switch project".bold())
                 .borders(Borders::all())
-                .border_type(BorderType::Rounded);
+                .border_type(BorderType::Rounded)
+                .style(app.theme.popup);
 
             let list = List::new(text)
                 .block(popup_block)
-                .highlight_style(Style::default().bold().light_blue());
+                .highlight_style(app.theme.popup_highlight);
 
             frame.render_stateful_widget(list, area, &mut state_wrapper(*index));
         }
@@ -306,13 +722,46 @@ pub fn draw_project_list(app: &App, frame: &mut Frame, index: usize) -> anyhow::
     Ok(())
 }
 
-pub fn draw_info_window(text: &str, r: Rect, f: &mut Frame) {
+/// Accounts from the config, marking the current one and any not logged in
+fn draw_account_list(app: &App, frame: &mut Frame, index: usize) {
+    let current = app.config.account().name;
+    let labels: Vec<String> = app
+        .config
+        .accounts()
+        .iter()
+        .map(|a| {
+            let mark = if a.name == current { "* " } else { "  " };
+            let status = if app.logins.contains_key(&a.name) { "" } else { ", not logged in" };
+            format!("{mark}{} ({}{status})", a.name, a.host)
+        })
+        .collect();
+
+    let merge = format!(" merged: {} ", if app.config.merge_accounts { "on" } else { "off" });
+    let width = labels.iter().fold(text::width(&merge), |max, l| cmp::max(max, text::width(l)));
+    let area = centered_rect(width as u16 + 2, labels.len() as u16 + 2, frame.size());
+
+    let popup_block = Block::default()
+        .title("Switch account".bold())
+        .title(block::Title::from(merge).position(block::Position::Bottom))
+        .borders(Borders::all())
+        .border_type(BorderType::Rounded)
+        .style(app.theme.popup);
+
+    let list = List::new(labels.into_iter().map(ListItem::new))
+        .block(popup_block)
+        .highlight_style(app.theme.popup_highlight);
+
+    frame.render_widget(Clear, area);
+    frame.render_stateful_widget(list, area, &mut state_wrapper(index));
+}
+
+pub fn draw_info_window(text: &str, style: Style, r: Rect, f: &mut Frame) {
     let lines = text.lines().count();
     let width = text
         .lines()
-        .fold(0, |max, accum| cmp::max(max, accum.len()));
+        .fold(0, |max, accum| cmp::max(max, text::width(accum)));
 
-    let paragraph = Paragraph::new(text).block(
+    let paragraph = Paragraph::new(text).style(style).block(
         Block::default()
             .borders(Borders::all())
             .border_type(BorderType::Rounded),
@@ -324,13 +773,261 @@ pub fn draw_info_window(text: &str, r: Rect, f: &mut Frame) {
     );
 }
 
-fn draw_editor(
-    frame: &mut Frame,
-    app: &App,
-    lists_layout: &Rc<[Rect]>,
-    offset: usize,
-) -> anyhow::Result<()> {
-    let mut position = lists_layout[app.field_state - offset].clone();
+/// Filter and sort shown after the project title
+fn view_summary(app: &App) -> String {
+    let mut summary = String::new();
+
+    if let Some(filter) = &app.filter {
+        summary.push_str(&format!("  filter: {} ({} hidden)", filter.query(), app.hidden_items.len()));
+    }
+
+    if !app.sort.is_empty() {
+        let keys: Vec<String> = app
+            .sort
+            .iter()
+            .map(|sort| format!("{} {}", sort.field, if sort.descending { "↓" } else { "↑" }))
+            .collect();
+        summary.push_str(&format!("  sort: {}", keys.join(", ")));
+    }
+
+    if let Some(group) = &app.group_by {
+        summary.push_str(&format!("  group: {group}"));
+    }
+
+    summary
+}
+
+/// Searchable list of actions with the keys bound to them
+fn draw_palette(app: &App, query: &LineEditor, index: usize, r: Rect, f: &mut Frame) {
+    let matches = action::search(query.text());
+
+    let width = cmp::min(50, r.width);
+    let height = cmp::min(matches.len() as u16 + 3, r.height);
+    let area = centered_rect(width, height, r);
+
+    let block = Block::default()
+        .borders(Borders::all())
+        .border_type(BorderType::Rounded)
+        .title(format!(": {}", query.text()))
+        .style(app.theme.popup);
+    let inner = block.inner(area);
+
+    let items: Vec<ListItem> = matches
+        .iter()
+        .map(|a| {
+            let keys = app.bindings.keys(Mode::Normal, *a).join(", ");
+            let gap = (inner.width as usize).saturating_sub(text::width(a.description()) + text::width(&keys));
+            ListItem::new(Line::from(vec![
+                Span::from(a.description()),
+                Span::from(" ".repeat(gap)),
+                Span::styled(keys, app.theme.hint),
+            ]))
+        })
+        .collect();
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(
+        List::new(items)
+            .block(block)
+            .highlight_style(app.theme.cursor),
+        area,
+        &mut state_wrapper(index),
+    );
+
+    // The query is typed into the title
+    f.set_cursor(area.x + 3 + query.cursor_width() as u16, area.y);
+}
+
+/// Every binding by mode, with the text editing keys that can't be changed
+fn draw_help(app: &App, scroll: u16, r: Rect, f: &mut Frame) {
+    let mut lines: Vec<Line> = Vec::new();
+
+    for mode in Mode::ALL {
+        lines.push(Line::from(mode.name().bold()));
+
+        // Grouped by action, in the order actions are declared
+        let mut actions: Vec<Action> = Vec::new();
+        for binding in app.bindings.mode(mode) {
+            if !actions.contains(&binding.action) {
+                actions.push(binding.action);
+            }
+        }
+        actions.sort();
+
+        for action in actions {
+            let keys = app.bindings.keys(mode, action).join(", ");
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {keys:<16} "), app.theme.key),
+                Span::from(action.description()),
+            ]));
+        }
+        lines.push(Line::from(""));
+    }
+
+    lines.push(Line::from("Text editing".bold()));
+    for (keys, description) in [
+        ("Ctrl-a, Home", "Start of line"),
+        ("Ctrl-e, End", "End of line"),
+        ("Alt-b, Alt-f", "Word left/right"),
+        ("Ctrl-w", "Delete word"),
+        ("Ctrl-u, Ctrl-k", "Delete to start/end"),
+        ("Ctrl-y", "Paste"),
+        ("Up, Down", "Previous/next value"),
+    ] {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {keys:<16} "), app.theme.key),
+            Span::from(description),
+        ]));
+    }
+
+    let area = centered_rect(cmp::min(60, r.width), r.height, r);
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines).scroll((scroll, 0)).style(app.theme.popup).block(
+            Block::default()
+                .borders(Borders::all())
+                .border_type(BorderType::Rounded)
+                .title("Keys (Esc to close)"),
+        ),
+        area,
+    );
+}
+
+/// Burn-down or burn-up of the current iteration above the items per status
+/// and the open work of each assignee
+fn draw_insights(app: &App, burnup: bool, r: Rect, f: &mut Frame) {
+    let Ok(info) = app.info() else {
+        return;
+    };
+    let points_field = insights::points_field(&info.fields, app.field_state);
+    let unit = points_field.map_or("items", |field| field.get_name());
+
+    let block = |title: String| Block::default().borders(Borders::all()).title(title);
+
+    f.render_widget(Clear, r);
+    let outer = block(String::from("Insights (b burn-up/down, Esc to close)"))
+        .border_type(BorderType::Rounded)
+        .style(app.theme.popup);
+    let inner = outer.inner(r);
+    f.render_widget(outer, r);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(inner);
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[1]);
+
+    match insights::burndown(&info.fields, &info.items, points_field, dates::today(app.utc_offset)) {
+        Some(burndown) => {
+            let (line, guide, title) = match burnup {
+                true => (burndown.done.clone(), vec![(0.0, burndown.total), (burndown.days as f64, burndown.total)], "Burn-up"),
+                false => (burndown.remaining(), vec![(0.0, burndown.total), (burndown.days as f64, 0.0)], "Burn-down"),
+            };
+            let guide_name = if burnup { "Scope" } else { "Ideal" };
+            let top = burndown.total.max(1.0);
+
+            let datasets = vec![
+                Dataset::default()
+                    .name(guide_name)
+                    .marker(symbols::Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(app.theme.hint)
+                    .data(&guide),
+                Dataset::default()
+                    .name(if burnup { "Done" } else { "Remaining" })
+                    .marker(symbols::Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(app.theme.number)
+                    .data(&line),
+            ];
+
+            let chart = Chart::new(datasets)
+                .block(block(format!("{title} of {} ({unit})", burndown.iteration)))
+                .x_axis(
+                    Axis::default()
+                        .bounds([0.0, burndown.days as f64])
+                        .labels(vec![Span::from(burndown.start.to_string()), Span::from(burndown.end().to_string())]),
+                )
+                .y_axis(
+                    Axis::default()
+                        .bounds([0.0, top])
+                        .labels(vec![Span::from("0"), Span::from(app.config.display_number(unit, top as f32))]),
+                );
+            f.render_widget(chart, rows[0]);
+        }
+        None => f.render_widget(
+            Paragraph::new("No iteration field in this project").block(block(String::from("Burn-down"))),
+            rows[0],
+        ),
+    }
+
+    match insights::status_field(&info.fields) {
+        Some(field) => {
+            let counts = insights::status_counts(field, &info.items);
+            let bars: Vec<Bar> = counts
+                .iter()
+                .map(|(name, count)| Bar::default().value(*count).label(Line::from(name.clone())))
+                .collect();
+            let width = counts.iter().map(|(name, _)| text::width(name) as u16).max().unwrap_or(3);
+
+            f.render_widget(
+                BarChart::default()
+                    .block(block(format!("Items by {}", field.get_name())))
+                    .data(BarGroup::default().bars(&bars))
+                    .bar_width(width.clamp(3, 12))
+                    .bar_style(app.theme.number),
+                bottom[0],
+            );
+        }
+        None => f.render_widget(
+            Paragraph::new("No single select field in this project").block(block(String::from("Items by status"))),
+            bottom[0],
+        ),
+    }
+
+    // Bars go sideways so long logins fit
+    let workload = insights::workload(&info.fields, &info.items, points_field);
+    let bars: Vec<Bar> = workload
+        .iter()
+        .map(|(name, points)| {
+            Bar::default()
+                .value(points.round().max(0.0) as u64)
+                .text_value(app.config.display_number(unit, *points as f32))
+                .label(Line::from(name.clone()))
+        })
+        .collect();
+
+    f.render_widget(
+        BarChart::default()
+            .block(block(format!("Open {unit} per assignee")))
+            .data(BarGroup::default().bars(&bars))
+            .direction(Direction::Horizontal)
+            .bar_width(1)
+            .bar_gap(0)
+            .bar_style(app.theme.number),
+        bottom[1],
+    );
+}
+
+/// Info window with a line of editable text after a label
+pub fn draw_prompt(label: &str, editor: &LineEditor, style: Style, r: Rect, f: &mut Frame) {
+    let text = format!("{label}{}", editor.text());
+    draw_info_window(&text, style, r, f);
+
+    // Matches where draw_info_window puts its text
+    let area = centered_rect(text::width(&text) as u16 + 4, 3, r);
+    f.set_cursor(
+        area.x + 1 + (text::width(label) + editor.cursor_width()) as u16,
+        area.y + 1,
+    );
+}
+
+/// Draws the editor for the current field, returning the area of any popup
+/// so it can be clicked, and how far its list is scrolled
+fn draw_editor(frame: &mut Frame, app: &App, mut position: Rect) -> anyhow::Result<Option<(Rect, usize)>> {
 
     use ProjectV2ItemField::*;
     match app.get_field_at(app.item_state, app.field_state)? {
@@ -340,48 +1037,67 @@ fn draw_editor(
             number: _,
             field: _,
         } => {
-            position.y = position.y + (app.item_state as u16);
+            position.y += app.cursor_row();
             position.height = 1;
 
             frame.render_widget(Clear, position);
 
-            if let FieldBuffer::Text(text, cursor_pos) = &app.input {
+            if let FieldBuffer::Text(editor) = &app.input {
                 frame.render_widget(
-                    Paragraph::new(text.clone()).style(Style::default().italic()),
+                    Paragraph::new(editor.text().to_string()).style(Style::default().italic()),
                     position,
                 );
 
-                frame.set_cursor(position.x + cursor_pos, position.y);
+                frame.set_cursor(position.x + editor.cursor_width() as u16, position.y);
             }
+
+            // Shown under the input until the number can be saved
+            if matches!(app.get_field_at(app.item_state, app.field_state)?, NumberValue { .. }) {
+                if let Some(error) = app.number_error() {
+                    let area = keep_on_screen(
+                        Rect::new(position.x, position.y + 1, text::width(&error) as u16, 1),
+                        frame.size(),
+                    );
+
+                    frame.render_widget(Clear, area);
+                    frame.render_widget(Paragraph::new(error).style(app.theme.error), area);
+                }
+            }
+
+            return Ok(Some((position, 0)));
         }
 
         // With options
         SingleSelectValue { name: _, field: _ } => {
             if let Field::ProjectV2SingleSelectField(field) = &app.info()?.fields[app.field_state] {
-                position.y = position.y + (app.item_state as u16); //- (app.input.current_option as u16);
+                position.y += app.cursor_row();
 
                 position.x -= 1;
                 position.width += 1;
                 position.height = field.options.len() as u16;
+                position = keep_on_screen(position, frame.size());
 
                 let block = Block::new().borders(Borders::LEFT | Borders::RIGHT);
 
                 let option_names: Vec<ListItem> = field
                     .options
                     .iter()
-                    .map(|n| ListItem::new(n.name.clone()).style(n.style()))
+                    .map(|n| ListItem::new(n.name.clone()).style(n.style(&app.theme)))
                     .collect();
 
                 if let FieldBuffer::SingleSelect(_options, index) = &app.input {
                     frame.render_widget(Clear, position);
 
+                    let mut state = state_wrapper(*index as usize);
                     frame.render_stateful_widget(
                         List::new(option_names)
                             .block(block)
-                            .highlight_style(Style::new().reversed()),
+                            .highlight_style(app.theme.cursor),
                         position,
-                        &mut state_wrapper(*index as usize),
+                        &mut state,
                     );
+
+                    return Ok(Some((position, state.offset())));
                 }
             }
         }
@@ -395,23 +1111,42 @@ fn draw_editor(
 
         // Date, calendar widget?
         DateValue { date: _, field: _ } => {
-            if let FieldBuffer::Date(date) = app.input {
+            if let FieldBuffer::Date(date, entry) = &app.input {
+                let date = *date;
                 let mut events = CalendarEventStore::default();
-                events.add(date, Style::default().on_red());
+                events.add(date, app.theme.calendar_day);
 
                 let calendar_widget = calendar::Monthly::new(date, events)
                     .show_month_header(Style::default())
                     .show_weekdays_header(Style::new().italic())
-                    .show_surrounding(Style::new().gray())
+                    .show_surrounding(app.theme.hint)
                     .block(Block::new().borders(Borders::all()));
 
-                position.height = 10;
+                // The calendar with a line for typing a date under it
+                position.height = 11;
                 position.width = 24;
                 position.x -= cmp::min(position.x, 6);
-                position.y = position.y + (app.item_state as u16); //- (app.input.current_option as u16);
+                position.y += app.cursor_row();
+                position = keep_on_screen(position, frame.size());
                 frame.render_widget(Clear, position);
 
-                frame.render_widget(calendar_widget, position);
+                let calendar_area = Rect { height: 10, ..position };
+                let entry_area = Rect { y: position.y + 10, height: 1, ..position };
+
+                frame.render_widget(calendar_widget, calendar_area);
+
+                let entry_line = match app.date_error() {
+                    _ if entry.is_empty() => Paragraph::new("type a date, . today").style(app.theme.hint).italic(),
+                    Some(_) => Paragraph::new(entry.text().to_string()).style(app.theme.error),
+                    None => Paragraph::new(entry.text().to_string()),
+                };
+                frame.render_widget(entry_line, entry_area);
+
+                if !entry.is_empty() {
+                    frame.set_cursor(entry_area.x + entry.cursor_width() as u16, entry_area.y);
+                }
+
+                return Ok(Some((calendar_area, 0)));
             }
         }
 
@@ -419,7 +1154,7 @@ fn draw_editor(
         Empty(_v) => {}
     }
 
-    Ok(())
+    Ok(None)
 }
 
 fn centered_rect(width: u16, height: u16, f: Rect) -> Rect {
@@ -444,11 +1179,20 @@ fn centered_rect(width: u16, height: u16, f: Rect) -> Rect {
         .split(popup_layout[1])[1] // Return the middle chunk
 }
 
+/// Moves a popup up so that its bottom edge stays inside the frame
+fn keep_on_screen(mut r: Rect, area: Rect) -> Rect {
+    r.height = r.height.min(area.height);
+    if r.bottom() > area.bottom() {
+        r.y = area.bottom() - r.height;
+    }
+    r
+}
+
 fn state_wrapper(i: usize) -> ListState {
     ListState::default().with_selected(Some(i))
 }
 
-fn find_minimum_offset(widths: &Vec<u16>, state: usize, max_width: u16) -> usize {
+fn find_minimum_offset(widths: &[u16], state: usize, max_width: u16) -> usize {
     for i in 0..widths.len() {
         if widths[i..state + 1].iter().sum::<u16>() < max_width {
             return i;
@@ -458,47 +1202,66 @@ fn find_minimum_offset(widths: &Vec<u16>, state: usize, max_width: u16) -> usize
     0
 }
 
-// [1, 2, 3, 4], split at 2 => [3, 4, 1, 2]
-// for wrapping fields around, no longer in use due to being tacky
-fn split_shift(v: &Vec<Constraint>, index: usize) -> Vec<Constraint> {
-    let (before, after) = v.split_at(index);
+/// Indices of the fields to draw, the frozen ones first followed by as many
+/// of the rest as fit while keeping the current field on screen
+fn visible_columns(widths: &[u16], state: usize, frozen: usize, max_width: u16) -> Vec<usize> {
+    let frozen = frozen.min(widths.len());
+    let frozen_width: u16 = widths[..frozen].iter().sum();
 
-    let mut new_vec = Vec::<Constraint>::new();
-    new_vec.extend_from_slice(after);
-    new_vec.extend_from_slice(before);
+    let offset = if state < frozen {
+        frozen
+    } else {
+        frozen
+            + find_minimum_offset(
+                &widths[frozen..],
+                state - frozen,
+                max_width.saturating_sub(frozen_width),
+            )
+    };
 
-    new_vec
+    (0..frozen).chain(offset..widths.len()).collect()
+}
+
+fn column_constraints(widths: &[u16], columns: &[usize]) -> Vec<Constraint> {
+    let mut constraints: Vec<Constraint> =
+        columns.iter().map(|c| Constraint::Length(widths[*c])).collect();
+    constraints.push(Constraint::Min(0));
+    constraints
 }
 
 fn get_headers(fields: &Vec<Field>, widths: &Vec<u16>) -> Vec<String> {
     (0..fields.len())
         .map(|i| {
-            format!(
-                "{:─<w$}",
-                fields[i].get_name(),
-                w = (widths[i] as usize - 1)
-            )
+            let width = (widths[i] as usize).saturating_sub(1);
+            text::pad(&text::truncate(fields[i].get_name(), width), width, '─')
         })
         .collect()
 }
 
-fn draw_list<'a>(items: &'a Vec<Item>, fields: &'a Vec<Field>, index: usize) -> List<'a> {
-    List::new(get_column(items, fields, index))
+fn draw_list<'a>(app: &App, rows: &[TableRow], index: usize, label: bool, width: u16) -> List<'a> {
+    List::new(get_column(app, rows, index, label, width))
         .block(Block::default())
         .highlight_style(Style::new().reversed())
 }
 
 fn guide<'a>(app: &App) -> Table<'a> {
+    let keys = &app.bindings;
+    let field_type = app.info().map_or("", |info| info.fields[app.field_state].get_type());
+
     let rows_raw = match app.menu_state {
-        InputMode::Input => insert_mode_guide(),
-        InputMode::SwitchProject(_) => switch_proj_guide(),
-        _ => normal_mode_guide(),
+        InputMode::Input if field_type == "NUMBER" => number_mode_guide(keys),
+        InputMode::Input if field_type == "DATE" => date_mode_guide(keys),
+        InputMode::Input if field_type == "SINGLE_SELECT" => list_guide(keys),
+        InputMode::Input => insert_mode_guide(keys),
+        InputMode::SwitchProject(_) => list_guide(keys),
+        InputMode::SwitchAccount(_) => account_guide(keys),
+        _ => normal_mode_guide(keys),
     };
 
     let rows = rows_raw.iter().map(|r| {
         Row::new(
             r.iter()
-                .map(|i| Line::from(vec![i.0.clone().bold(), Span::from(i.1.clone())])),
+                .map(|i| Line::from(vec![Span::styled(i.0.clone(), app.theme.key), Span::from(i.1.clone())])),
         )
     });
 
@@ -507,89 +1270,212 @@ fn guide<'a>(app: &App) -> Table<'a> {
     Table::new(rows, widths)
 }
 
-fn switch_proj_guide() -> Vec<Vec<(String, String)>> {
+/// First keys of several actions, written together like `hjkl` when they are
+/// single characters and as `Up/Down` otherwise
+fn keys_for(keys: &Bindings, mode: Mode, actions: &[Action]) -> String {
+    let names: Vec<String> = actions.iter().map(|a| keys.key(mode, *a)).collect();
+
+    if names.iter().all(|n| n.chars().count() == 1) {
+        names.concat()
+    } else {
+        names.join("/")
+    }
+}
+
+fn list_guide(keys: &Bindings) -> Vec<Vec<(String, String)>> {
     vec![
         vec![
-            (String::from("Esc"), String::from(" exit")),
-            (String::from("j"), String::from(" down")),
+            (keys.key(Mode::List, Action::Cancel), String::from(" exit")),
+            (keys.key(Mode::List, Action::Down), String::from(" down")),
         ],
         vec![
-            (String::from("Enter"), String::from(" select")),
-            (String::from("k"), String::from(" up")),
+            (keys.key(Mode::List, Action::Confirm), String::from(" select")),
+            (keys.key(Mode::List, Action::Up), String::from(" up")),
         ],
     ]
 }
 
-fn insert_mode_guide() -> Vec<Vec<(String, String)>> {
+fn account_guide(keys: &Bindings) -> Vec<Vec<(String, String)>> {
+    let mut rows = list_guide(keys);
+    rows[0].push((String::from("m"), String::from(" merge projects")));
+    rows
+}
+
+fn insert_mode_guide(keys: &Bindings) -> Vec<Vec<(String, String)>> {
     vec![
         vec![
-            (String::from("Esc"), String::from(" exit")),
-            (String::from(" "), String::from(" ")),
-            (String::from(" "), String::from(" ")),
-            (String::from(" "), String::from(" ")),
-            (String::from(" "), String::from(" ")),
+            (keys.key(Mode::Prompt, Action::Cancel), String::from(" exit")),
+            (String::from("Ctrl-w"), String::from(" delete word")),
+            (String::from("Ctrl-u"), String::from(" delete to start")),
+            (String::from("Ctrl-y"), String::from(" paste")),
+            (String::from("Up"), String::from(" previous value")),
         ],
         vec![
-            (String::from("Enter"), String::from(" save")),
-            (String::from(" "), String::from(" ")),
+            (keys.key(Mode::Prompt, Action::Confirm), String::from(" save")),
+            (String::from("Alt-b/f"), String::from(" word left/right")),
+            (String::from("Ctrl-k"), String::from(" delete to end")),
+            (String::from("Home/End"), String::from(" start/end")),
+            (String::from("Down"), String::from(" next value")),
+        ],
+    ]
+}
+
+fn number_mode_guide(keys: &Bindings) -> Vec<Vec<(String, String)>> {
+    vec![
+        vec![
+            (keys.key(Mode::Prompt, Action::Cancel), String::from(" exit")),
+            (String::from("Up"), String::from(" +1")),
+            (String::from("PgUp"), String::from(" +10")),
+            (String::from("Ctrl-w"), String::from(" delete word")),
+            (String::from("Home/End"), String::from(" start/end")),
+        ],
+        vec![
+            (keys.key(Mode::Prompt, Action::Confirm), String::from(" save")),
+            (String::from("Down"), String::from(" -1")),
+            (String::from("PgDn"), String::from(" -10")),
+            (String::from("Ctrl-u"), String::from(" delete to start")),
             (String::from(" "), String::from(" ")),
+        ],
+    ]
+}
+
+fn date_mode_guide(keys: &Bindings) -> Vec<Vec<(String, String)>> {
+    let calendar = |actions: &[Action]| keys_for(keys, Mode::Calendar, actions);
+
+    vec![
+        vec![
+            (calendar(&[Action::Cancel]), String::from(" exit")),
+            (calendar(&[Action::Left, Action::Right]), String::from(" day")),
+            (calendar(&[Action::PreviousYear, Action::NextYear]), String::from(" year")),
+            (calendar(&[Action::Today]), String::from(" today")),
             (String::from(" "), String::from(" ")),
+        ],
+        vec![
+            (calendar(&[Action::Confirm]), String::from(" save")),
+            (calendar(&[Action::Down, Action::Up]), String::from(" week")),
+            (calendar(&[Action::NextMonth, Action::PreviousMonth]), String::from(" month")),
+            (String::from("type"), String::from(" +3d, next fri")),
             (String::from(" "), String::from(" ")),
         ],
     ]
 }
 
-fn normal_mode_guide() -> Vec<Vec<(String, String)>> {
+fn normal_mode_guide(keys: &Bindings) -> Vec<Vec<(String, String)>> {
+    let normal = |actions: &[Action]| keys_for(keys, Mode::Normal, actions);
+
     vec![
         vec![
-            (String::from("q"), String::from(" quit")),
-            (String::from("i"), String::from(" insert")),
-            (String::from("a"), String::from(" add new")),
-            (String::from("h"), String::from(" left")),
-            (String::from("l"), String::from(" right")),
+            (normal(&[Action::Quit]), String::from(" quit")),
+            (normal(&[Action::Edit]), String::from(" insert")),
+            (normal(&[Action::AddItem]), String::from(" add new")),
+            (normal(&[Action::Left, Action::Down, Action::Up, Action::Right]), String::from(" move")),
+            (normal(&[Action::CommandPalette]), String::from(" commands")),
         ],
         vec![
-            (String::from("p"), String::from(" switch project")),
-            (String::from("x"), String::from(" hide field")),
-            (String::from("s"), String::from(" sort by")),
-            (String::from("k"), String::from(" up")),
-            (String::from("j"), String::from(" down")),
+            (normal(&[Action::SwitchProject]), String::from(" switch project")),
+            (normal(&[Action::HideField]), String::from(" hide field")),
+            (normal(&[Action::SortBy]), String::from(" sort by")),
+            (normal(&[Action::Filter]), String::from(" filter")),
+            (normal(&[Action::Help]), String::from(" all keys")),
         ],
     ]
 }
 
-fn get_column<'a>(items: &'a Vec<Item>, fields: &'a Vec<Field>, index: usize) -> Vec<ListItem<'a>> {
-    items
-        .iter()
-        .map(|item| {
-            let item = item.field_values.get_from_field(fields[index].get_name());
-            ListItem::new(item.value()).style(item.style())
+/// Cells of one field for each row. Group headers show the group's name and
+/// size in the `label` column and totals in number columns.
+fn get_column<'a>(app: &App, rows: &[TableRow], index: usize, label: bool, width: u16) -> Vec<ListItem<'a>> {
+    let Ok(info) = app.info() else {
+        return Vec::new();
+    };
+    let field = &info.fields[index];
+    let groups = app.groups();
+    let group_field = app.group_field();
+
+    rows.iter()
+        .map(|row| match row {
+            TableRow::Item(item) => {
+                let value = info.items[*item].field_values.get_from_field(field.get_name());
+                ListItem::new(text::truncate(&app.config.display_value(value), (width as usize).saturating_sub(1)))
+                    .style(value.style(&app.theme))
+            }
+            TableRow::Group(group) => {
+                let group = &groups[*group];
+                let text = if label {
+                    let marker = if app.collapsed.contains(&group.value) { "▸" } else { "▾" };
+                    let name = match (group.value.is_empty(), group_field) {
+                        (true, Some(group_field)) => format!("No {}", group_field.get_name()),
+                        _ => group.value.clone(),
+                    };
+                    format!("{marker} {name} ({})", group.items.len())
+                } else if field.get_type() == "NUMBER" {
+                    let sum = group::sum(&info.items, group, field);
+                    app.config.display_number(field.get_name(), sum)
+                } else {
+                    String::new()
+                };
+                ListItem::new(text::truncate(&text, (width as usize).saturating_sub(1))).style(app.theme.group)
+            }
         })
         .collect()
 }
 
-fn constrained_widths(i: &Vec<u16>) -> Vec<Constraint> {
-    let mut widths: Vec<Constraint> = i.iter().map(|f| Constraint::Length(*f)).collect();
-    widths.push(Constraint::Min(0));
-    widths
-}
+/// Widths of every column including a space between them. Columns fit their
+/// content up to the configured limit, unless resized by hand, and are
+/// squeezed into `available` in auto-fit mode.
+fn get_widths(app: &App, fields: &[Field], items: &[Item], available: u16) -> Vec<u16> {
+    let project_id = app.project_id().unwrap_or_default();
 
-fn get_widths(app: &App, fields: &Vec<Field>, items: &Vec<Item>) -> Vec<u16> {
-    fields
+    let mut widths: Vec<u16> = fields
         .iter()
-        .map(|field| get_width(app, field, fields, items) as u16 + 1)
-        .collect()
+        .map(|field| {
+            let natural = get_width(&app.config, field, items) as u16 + 1;
+            app.config
+                .column_width(&project_id, field.get_name())
+                .unwrap_or_else(|| natural.min(app.config.max_column_width))
+        })
+        .collect();
+
+    if app.config.fit_to_terminal {
+        fit_widths(&mut widths, available);
+    }
+
+    // The field being edited grows to fit its input
+    if let Some(width) = widths.get_mut(app.field_state) {
+        *width = cmp::max(*width, app.input.len() as u16 + 1);
+    }
+
+    widths
 }
 
-fn get_width(app: &App, field: &Field, fields: &Vec<Field>, items: &Vec<Item>) -> usize {
-    let currently_editing = field.get_id() == fields[app.field_state].get_id();
+/// Caps the widest columns until they all fit in the available width
+fn fit_widths(widths: &mut [u16], available: u16) {
+    if widths.iter().sum::<u16>() <= available {
+        return;
+    }
 
+    // Find the largest cap that still fits, but never go below a readable width
+    let (mut low, mut high) = (MIN_COLUMN_WIDTH, *widths.iter().max().unwrap_or(&0));
+    while low < high {
+        let cap = (low + high).div_ceil(2);
+        if widths.iter().map(|w| cmp::min(*w, cap)).sum::<u16>() <= available {
+            low = cap;
+        } else {
+            high = cap - 1;
+        }
+    }
+
+    for width in widths.iter_mut() {
+        *width = cmp::min(*width, low);
+    }
+}
+
+fn get_width(config: &Config, field: &Field, items: &[Item]) -> usize {
     let max = cmp::max(
-        field.get_name().len(),
+        text::width(field.get_name()),
         match &field {
             Field::ProjectV2SingleSelectField(field) => field.options.iter().fold(0, |max, s| {
-                if s.name.len() > max {
-                    s.name.len()
+                if text::width(&s.name) > max {
+                    text::width(&s.name)
                 } else {
                     max
                 }
@@ -599,7 +1485,7 @@ fn get_width(app: &App, field: &Field, fields: &Vec<Field>, items: &Vec<Item>) -
 
             // pure string
             _ => items.iter().fold(0, |max, i| {
-                let l = i.field_values.name_from_field(&*field.get_name()).len();
+                let l = text::width(&config.display_value(i.field_values.get_from_field(field.get_name())));
                 if l > max {
                     l
                 } else {
@@ -609,9 +1495,5 @@ fn get_width(app: &App, field: &Field, fields: &Vec<Field>, items: &Vec<Item>) -
         },
     );
 
-    if currently_editing {
-        cmp::max(max, app.input.len())
-    } else {
-        max
-    }
+    max
 }
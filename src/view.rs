@@ -0,0 +1,63 @@
+//! Named views of a project, each a filter, sort, grouping and set of fields
+//! shown. The project's views on GitHub are loaded with it, and more can be
+//! saved in the config:
+//!
+//! ```toml
+//! [[views]]
+//! project_id = "PVT_kwHOAbc"
+//! default = "My bugs"
+//!
+//! [[views.views]]
+//! name = "My bugs"
+//! filter = "assignee:octocat label:bug"
+//! sort = [{ field = "Priority" }, { field = "Size", descending = true }]
+//! group_by = "Status"
+//! fields = ["Title", "Status", "Priority"]
+//! ```
+
+use crate::sort::Sort;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct View {
+    pub name: String,
+    #[serde(default)]
+    pub filter: String,
+    /// Sort keys, the first one wins
+    #[serde(default)]
+    pub sort: Vec<Sort>,
+    #[serde(default)]
+    pub group_by: Option<String>,
+    /// Fields shown, every field when empty
+    #[serde(default)]
+    pub fields: Vec<String>,
+    /// GitHub's layout for the view, such as `BOARD_LAYOUT`. Every view is
+    /// shown as a table here.
+    #[serde(skip)]
+    pub layout: String,
+    /// Saved in the config rather than on GitHub
+    #[serde(skip)]
+    pub local: bool,
+}
+
+impl View {
+    /// Name shown in the tabs row
+    pub fn label(&self) -> String {
+        match self.layout.as_str() {
+            "BOARD_LAYOUT" => format!("{} (board)", self.name),
+            "ROADMAP_LAYOUT" => format!("{} (roadmap)", self.name),
+            _ => self.name.clone(),
+        }
+    }
+}
+
+/// Views saved in the config for one project
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ProjectViews {
+    pub project_id: String,
+    /// Name of the view the project opens with
+    #[serde(default)]
+    pub default: Option<String>,
+    #[serde(default)]
+    pub views: Vec<View>,
+}